@@ -7,7 +7,7 @@ extern crate diesel as other_diesel;
 extern crate serde_derive;
 
 use diesel::{
-    self, delete, insert_into, prelude::*, result::Error, update, Associations, FromSqlRow, Identifiable,
+    self, delete, insert_into, prelude::*, update, Associations, FromSqlRow, Identifiable,
     Insertable, Queryable,
 };
 use postgres_resource::*;
@@ -0,0 +1,345 @@
+use crate::{builder::*, hooks::*, IdentExt};
+
+use syn::parse::Result;
+
+pub struct BelongsTo;
+
+impl<'i> Builder<'i> for BelongsTo {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let mut associations = Vec::new();
+
+        input.parsed_struct.fields.iter().for_each(|field| {
+            if let Some(target) = field.fk_target() {
+                let parent = target.append("WithId");
+                let foreign_key = field.column().to_string();
+                associations.push(quote!(#[belongs_to(#parent, foreign_key = #foreign_key)]));
+            }
+        });
+
+        Ok(quote!(#(#associations)*))
+    }
+}
+
+pub struct ParentLoaders;
+
+impl<'i> Builder<'i> for ParentLoaders {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let connection = DatabaseConnection.build(input)?;
+        let model_with_id = input.parsed_struct.model_name_with_id();
+        let controller = input.parsed_struct.controller_name();
+
+        let mut loaders = Vec::new();
+
+        input.parsed_struct.fields.iter().for_each(|field| {
+            if let Some(target) = field.fk_target() {
+                let name = &field.name;
+                let method = target.snake_case();
+                let parent = target.append("WithId");
+                let parent_schema = schema_of(target);
+
+                if field.is_option() {
+                    loaders.push(quote! {
+                        pub fn #method(&self, model: &#model_with_id) -> Result<Option<#parent>, ResourceError> {
+                            match model.#name {
+                                Some(id) => Ok(Some(#parent_schema::table
+                                   .find(id)
+                                   .get_result::<#parent>(#connection)?)),
+                                None => Ok(None),
+                            }
+                        }
+                    });
+                } else {
+                    loaders.push(quote! {
+                        pub fn #method(&self, model: &#model_with_id) -> Result<#parent, ResourceError> {
+                            Ok(#parent_schema::table
+                               .find(model.#name)
+                               .get_result::<#parent>(#connection)?)
+                        }
+                    });
+                }
+            }
+        });
+
+        if loaders.is_empty() {
+            return Ok(quote!());
+        }
+
+        Ok(quote! {
+            impl #controller {
+                #(#loaders)*
+            }
+        })
+    }
+}
+
+pub struct ChildLoaders;
+
+impl<'i> Builder<'i> for ChildLoaders {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if input.parsed_struct.attrs.has_many.is_empty() {
+            return Ok(quote!());
+        }
+
+        let schema = Schema.build(input)?;
+        let connection = DatabaseConnection.build(input)?;
+        let scope = Scope.build(input)?;
+        let model_with_id = input.parsed_struct.model_name_with_id();
+        let controller = input.parsed_struct.controller_name();
+
+        let loaders = input.parsed_struct.attrs.has_many.iter().map(|child| {
+            let method = child.append("s").snake_case().prepend("load_with_");
+            let child_controller = child.append("Controller");
+            let child = child.append("WithId");
+
+            quote! {
+                pub fn #method(&self, by: Expr<#schema::table>) -> Result<Vec<(#model_with_id, Vec<#child>)>, ResourceError> {
+                    let connection = #connection;
+                    let parents = #schema::table
+                        .filter(by)
+                        #scope
+                        .get_results::<#model_with_id>(connection)?;
+                    let children = #child::belonging_to(&parents)
+                        .filter(#child_controller::scope())
+                        .load::<#child>(connection)?
+                        .grouped_by(&parents);
+
+                    Ok(parents.into_iter().zip(children).collect())
+                }
+            }
+        });
+
+        Ok(quote! {
+            impl #controller {
+                #(#loaders)*
+            }
+        })
+    }
+}
+
+pub struct ManyToManyRelations;
+
+impl<'i> Builder<'i> for ManyToManyRelations {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if input.parsed_struct.attrs.many_to_many.is_empty() {
+            return Ok(quote!());
+        }
+
+        let connection = DatabaseConnection.build(input)?;
+        let controller = input.parsed_struct.controller_name();
+        let own_key = input.parsed_struct.ident.snake_case().append("_id");
+        let id = input.parsed_struct.id_name();
+
+        let relations = input.parsed_struct.attrs.many_to_many.iter().map(|relation| {
+            let target = &relation.target;
+            let through = &relation.through;
+            let related = target.append("WithId");
+            let related_schema = schema_of(target);
+            let related_key = target.snake_case().append("_id");
+            let related_id = target.append("Id");
+            let related_controller = target.append("Controller");
+
+            let plural = target.append("s").snake_case();
+            let attach = plural.prepend("attach_");
+            let detach = plural.prepend("detach_");
+            let sync = plural.prepend("sync_");
+            let load = plural.prepend("load_");
+
+            quote! {
+                pub fn #attach(&self, id: #id, related: &[#related_id]) -> Result<usize, ResourceError> {
+                    let rows = related
+                        .iter()
+                        .map(|related_id| {
+                            (crate::schema::#through::#own_key.eq(id), crate::schema::#through::#related_key.eq(*related_id))
+                        })
+                        .collect::<Vec<_>>();
+
+                    Ok(insert_into(crate::schema::#through::table)
+                       .values(rows)
+                       .on_conflict_do_nothing()
+                       .execute(#connection)?)
+                }
+
+                pub fn #detach(&self, id: #id, related: &[#related_id]) -> Result<usize, ResourceError> {
+                    Ok(delete(crate::schema::#through::table)
+                       .filter(crate::schema::#through::#own_key.eq(id))
+                       .filter(crate::schema::#through::#related_key.eq_any(related))
+                       .execute(#connection)?)
+                }
+
+                pub fn #sync(&self, id: #id, related: &[#related_id]) -> Result<(), ResourceError> {
+                    let connection = #connection;
+                    connection.transaction::<_, ResourceError, _>(|| {
+                        delete(crate::schema::#through::table)
+                            .filter(crate::schema::#through::#own_key.eq(id))
+                            .filter(crate::schema::#through::#related_key.ne_all(related))
+                            .execute(connection)?;
+
+                        let rows = related
+                            .iter()
+                            .map(|related_id| {
+                                (crate::schema::#through::#own_key.eq(id), crate::schema::#through::#related_key.eq(*related_id))
+                            })
+                            .collect::<Vec<_>>();
+
+                        insert_into(crate::schema::#through::table)
+                            .values(rows)
+                            .on_conflict_do_nothing()
+                            .execute(connection)?;
+
+                        Ok(())
+                    })
+                }
+
+                pub fn #load(&self, id: #id) -> Result<Vec<#related>, ResourceError> {
+                    let connection = #connection;
+                    let related = crate::schema::#through::table
+                        .select(crate::schema::#through::#related_key)
+                        .filter(crate::schema::#through::#own_key.eq(id))
+                        .get_results::<#related_id>(connection)?;
+
+                    Ok(#related_schema::table
+                       .filter(#related_schema::id.eq_any(related))
+                       .filter(#related_controller::scope())
+                       .get_results::<#related>(connection)?)
+                }
+            }
+        });
+
+        Ok(quote! {
+            impl #controller {
+                #(#relations)*
+            }
+        })
+    }
+}
+
+pub struct NestedWrites;
+
+impl<'i> Builder<'i> for NestedWrites {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let connection = DatabaseConnection.build(input)?;
+        let insert_values = InsertValues.build(input)?;
+        let prepare = Prepare.build(input)?;
+        let prepare_all = PrepareAll.build(input)?;
+        let model = input.parsed_struct.inner_model_name();
+        let model_with_id = input.parsed_struct.model_name_with_id();
+        let controller = input.parsed_struct.controller_name();
+
+        let mut writes = Vec::new();
+
+        // Children inserted on behalf of a parent, one method per `#[fk(Resource)]`
+        input.parsed_struct.fields.iter().for_each(|field| {
+            if let Some(target) = field.fk_target() {
+                let column = field.column();
+                let parent_id = target.append("Id");
+                let method = target.snake_case().prepend("create_for_");
+
+                let insert = quote! {
+                    #prepare_all
+                    let rows = models
+                        .iter()
+                        .map(|model| (#insert_values, #schema::#column.eq(parent_id)))
+                        .collect::<Vec<_>>();
+                    let created = insert_into(#schema::table)
+                        .values(rows)
+                        .get_results::<#model_with_id>(connection)?;
+                };
+
+                let body = if hooks(input) {
+                    quote! {
+                        connection.transaction::<_, ResourceError, _>(|| {
+                            let mut models = models.to_vec();
+                            for model in &mut models {
+                                self.before_create(connection, model)?;
+                            }
+                            let models = &models[..];
+                            #insert
+                            for model in &created {
+                                self.after_create(connection, model)?;
+                            }
+                            Ok(created)
+                        })
+                    }
+                } else {
+                    quote! {
+                        #insert
+                        Ok(created)
+                    }
+                };
+
+                writes.push(quote! {
+                    pub fn #method(
+                        &self,
+                        parent_id: #parent_id,
+                        models: &[#model],
+                        connection: &diesel::pg::PgConnection,
+                    ) -> Result<Vec<#model_with_id>, ResourceError> {
+                        #body
+                    }
+                });
+            }
+        });
+
+        // Parents inserted together with their children, one method per `#[has_many(Resource)]`
+        let parent_key = input.parsed_struct.ident.snake_case();
+        input.parsed_struct.attrs.has_many.iter().for_each(|child| {
+            let children = child.append("s").snake_case();
+            let method = children.prepend("create_with_");
+            let create_for = parent_key.prepend("create_for_");
+            let child_model = child.clone();
+            let child_controller = child.append("Controller");
+            let child = child.append("WithId");
+
+            let insert = quote! {
+                let parent = insert_into(#schema::table)
+                    .values(#insert_values)
+                    .get_result::<#model_with_id>(connection)?;
+                let children = #child_controller.#create_for(parent.id, #children, connection)?;
+            };
+
+            let body = if hooks(input) {
+                let before = before_hook("before_create");
+                quote! {
+                    let connection = #connection;
+                    connection.transaction::<_, ResourceError, _>(|| {
+                        #before
+                        #prepare
+                        #insert
+                        self.after_create(connection, &parent)?;
+                        Ok((parent, children))
+                    })
+                }
+            } else {
+                quote! {
+                    #prepare
+                    let connection = #connection;
+                    connection.transaction::<_, ResourceError, _>(|| {
+                        #insert
+                        Ok((parent, children))
+                    })
+                }
+            };
+
+            writes.push(quote! {
+                pub fn #method(
+                    &self,
+                    model: &#model,
+                    #children: &[#child_model],
+                ) -> Result<(#model_with_id, Vec<#child>), ResourceError> {
+                    #body
+                }
+            });
+        });
+
+        if writes.is_empty() {
+            return Ok(quote!());
+        }
+
+        Ok(quote! {
+            impl #controller {
+                #(#writes)*
+            }
+        })
+    }
+}
@@ -1,14 +1,34 @@
 use syn::{
-    parse::{Parse, ParseStream, Result},
-    Attribute, Error, Lit, LitStr,
+    parse::{Parse, ParseStream, Parser, Result},
+    punctuated::Punctuated,
+    Attribute, Error, Ident, Lit, LitStr,
     Meta::*,
-    MetaNameValue,
+    MetaNameValue, NestedMeta, Path,
 };
 
+#[derive(Debug)]
+pub struct ManyToMany {
+    pub target: Ident,
+    pub through: Ident,
+}
+
 #[derive(Debug)]
 pub struct Attrs {
     pub db_conn: Option<LitStr>,
     pub table: Option<LitStr>,
+    pub timestamps: bool,
+    pub soft_delete: bool,
+    /// Whether the controller runs its `ResourceHooks` around writes.
+    pub hooks: bool,
+    pub has_many: Vec<Ident>,
+    pub many_to_many: Vec<ManyToMany>,
+    pub rename_all: Option<LitStr>,
+    /// Doc comments, forwarded to the generated structs and controller.
+    pub docs: Vec<Attribute>,
+    /// Derives added to the generated structs.
+    pub derives: Vec<Path>,
+    /// Attributes which are not resource attributes, forwarded to the generated structs.
+    pub forward: Vec<Attribute>,
 }
 
 impl Parse for Attrs {
@@ -17,17 +37,55 @@ impl Parse for Attrs {
 
         let mut db_conn: Option<LitStr> = None;
         let mut table: Option<LitStr> = None;
+        let mut timestamps = false;
+        let mut soft_delete = false;
+        let mut hooks = false;
+        let mut has_many = Vec::new();
+        let mut many_to_many = Vec::new();
+        let mut rename_all: Option<LitStr> = None;
+        let mut docs = Vec::new();
+        let mut derives = Vec::new();
+        let mut forward = Vec::new();
 
-        for attr in &attrs {
+        for attr in attrs {
             if attr.path.is_ident("table") {
-                table = Self::parse_attr(attr, "table")?;
-            }
-            if attr.path.is_ident("env_var") {
-                db_conn = Self::parse_attr(attr, "env_var")?;
+                table = Self::parse_attr(&attr, "table")?;
+            } else if attr.path.is_ident("env_var") {
+                db_conn = Self::parse_attr(&attr, "env_var")?;
+            } else if attr.path.is_ident("timestamps") {
+                timestamps = Self::parse_flag(&attr, "timestamps")?;
+            } else if attr.path.is_ident("soft_delete") {
+                soft_delete = Self::parse_flag(&attr, "soft_delete")?;
+            } else if attr.path.is_ident("hooks") {
+                hooks = Self::parse_flag(&attr, "hooks")?;
+            } else if attr.path.is_ident("has_many") {
+                has_many.extend(Self::parse_list(&attr, "has_many")?);
+            } else if attr.path.is_ident("many_to_many") {
+                many_to_many.push(Self::parse_many_to_many(&attr)?);
+            } else if attr.path.is_ident("rename_all") {
+                rename_all = Self::parse_attr(&attr, "rename_all")?;
+            } else if attr.path.is_ident("doc") {
+                docs.push(attr);
+            } else if attr.path.is_ident("derive") {
+                derives.extend(Self::parse_derive(&attr)?);
+            } else {
+                forward.push(attr);
             }
         }
 
-        Ok(Attrs { db_conn, table })
+        Ok(Attrs {
+            db_conn,
+            table,
+            timestamps,
+            soft_delete,
+            hooks,
+            has_many,
+            many_to_many,
+            rename_all,
+            docs,
+            derives,
+            forward,
+        })
     }
 }
 
@@ -42,4 +100,69 @@ impl Attrs {
             }
         }
     }
+
+    fn parse_flag(attr: &Attribute, expected: &str) -> Result<bool> {
+        match attr.parse_meta()? {
+            Word(_) => Ok(true),
+            _ => {
+                let error_span = attr.bracket_token.span;
+                let message = &format!("expected #[{}]", expected);
+                Err(Error::new(error_span, message))
+            }
+        }
+    }
+
+    fn parse_list(attr: &Attribute, expected: &str) -> Result<Vec<Ident>> {
+        let error_span = attr.bracket_token.span;
+        let message = &format!("expected #[{}(Resource, ...)]", expected);
+
+        match attr.parse_meta()? {
+            List(list) => list
+                .nested
+                .into_iter()
+                .map(|nested| match nested {
+                    NestedMeta::Meta(Word(ident)) => Ok(ident),
+                    _ => Err(Error::new(error_span, message)),
+                })
+                .collect(),
+            _ => Err(Error::new(error_span, message)),
+        }
+    }
+
+    pub fn parse_derive(attr: &Attribute) -> Result<Vec<Path>> {
+        let parser = |input: ParseStream| {
+            let content;
+            let _ = parenthesized!(content in input);
+            content.parse_terminated::<Path, Token![,]>(Path::parse_mod_style)
+        };
+
+        let derives: Punctuated<Path, Token![,]> = parser.parse2(attr.tts.clone())?;
+        Ok(derives.into_iter().collect())
+    }
+
+    fn parse_many_to_many(attr: &Attribute) -> Result<ManyToMany> {
+        let error_span = attr.bracket_token.span;
+        let message = "expected #[many_to_many(Resource, through = \"...\")]";
+
+        if let List(list) = attr.parse_meta()? {
+            let mut nested = list.nested.iter();
+            if let (
+                Some(NestedMeta::Meta(Word(target))),
+                Some(NestedMeta::Meta(NameValue(MetaNameValue {
+                    ident,
+                    lit: Lit::Str(through),
+                    ..
+                }))),
+                None,
+            ) = (nested.next(), nested.next(), nested.next())
+            {
+                if ident == "through" {
+                    let through = Ident::new(&through.value(), through.span());
+                    return Ok(ManyToMany { target: target.clone(), through });
+                }
+            }
+        }
+
+        Err(Error::new(error_span, message))
+    }
 }
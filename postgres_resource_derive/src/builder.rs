@@ -1,7 +1,10 @@
-use crate::{r#struct::*, IdentExt};
+use crate::{
+    encrypted::encrypt_step, hashed::hash_step, normalize::NormalizeSteps, r#struct::*,
+    validate::validates, IdentExt,
+};
 
 use proc_macro2::Span;
-use syn::{parse::Result, LitStr};
+use syn::{parse::Result, Ident, LitStr};
 
 pub struct Input {
     pub parsed_struct: Struct,
@@ -15,12 +18,19 @@ struct InferredTableMacro;
 
 impl<'i> Builder<'i> for InferredTableMacro {
     fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
-        let model_name = input.parsed_struct.ident.append("s").snake_case().to_string();
-        let literal = LitStr::new(&model_name, Span::call_site());
+        let literal = LitStr::new(&table_name(input), Span::call_site());
         Ok(quote!(#[table_name = #literal]))
     }
 }
 
+/// The table name, either given by `#[table = "..."]` or inferred from the struct name.
+pub fn table_name(input: &Input) -> String {
+    match input.parsed_struct.attrs.table {
+        Some(ref table) => table.value(),
+        None => input.parsed_struct.ident.append("s").snake_case().to_string(),
+    }
+}
+
 pub struct TableMacro;
 
 impl<'i> Builder<'i> for TableMacro {
@@ -37,11 +47,16 @@ pub struct Schema;
 
 impl<'i> Builder<'i> for Schema {
     fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
-        let model = input.parsed_struct.ident.append("s").snake_case();
-        Ok(quote!(crate::schema::#model))
+        Ok(schema_of(&input.parsed_struct.ident))
     }
 }
 
+/// The schema module of the resource named `resource`.
+pub fn schema_of(resource: &Ident) -> proc_macro2::TokenStream {
+    let model = resource.append("s").snake_case();
+    quote!(crate::schema::#model)
+}
+
 struct DefaultDatabaseConnection;
 
 impl<'i> Builder<'i> for DefaultDatabaseConnection {
@@ -49,7 +64,7 @@ impl<'i> Builder<'i> for DefaultDatabaseConnection {
         Ok(quote!(&self.connection()))
     }
 }
-        
+
 pub struct DatabaseConnection;
 
 impl<'i> Builder<'i> for DatabaseConnection {
@@ -61,3 +76,176 @@ impl<'i> Builder<'i> for DatabaseConnection {
         }
     }
 }
+
+pub struct InsertValues;
+
+impl<'i> Builder<'i> for InsertValues {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let mut values = Vec::new();
+
+        if input.parsed_struct.attrs.timestamps {
+            values.push(quote!(#schema::created_at.eq(diesel::dsl::now)));
+            values.push(quote!(#schema::updated_at.eq(diesel::dsl::now)));
+        }
+
+        if let Some(field) = input.parsed_struct.version_field() {
+            let column = field.column();
+            values.push(quote!(#schema::#column.eq(1)));
+        }
+
+        if values.is_empty() {
+            Ok(quote!(model))
+        } else {
+            Ok(quote!((model, #(#values,)*)))
+        }
+    }
+}
+
+pub struct Changeset;
+
+impl<'i> Builder<'i> for Changeset {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let mut values = Vec::new();
+
+        if input.parsed_struct.attrs.timestamps {
+            values.push(quote!(#schema::updated_at.eq(diesel::dsl::now)));
+        }
+
+        if let Some(field) = input.parsed_struct.version_field() {
+            let column = field.column();
+            values.push(quote!(#schema::#column.eq(#schema::#column + 1)));
+        }
+
+        if values.is_empty() {
+            Ok(quote!(model))
+        } else {
+            Ok(quote!((model, #(#values,)*)))
+        }
+    }
+}
+
+pub struct Scope;
+
+impl<'i> Builder<'i> for Scope {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+
+        if input.parsed_struct.attrs.soft_delete {
+            Ok(quote!(.filter(#schema::deleted_at.is_null())))
+        } else {
+            Ok(quote!())
+        }
+    }
+}
+
+/// The `scope` of the controller, the filter of `Scope` as an `Expr`, which the loaders of related
+/// resources apply to its rows.
+pub struct ScopeFn;
+
+impl<'i> Builder<'i> for ScopeFn {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let controller = input.parsed_struct.controller_name();
+
+        let scope = if input.parsed_struct.attrs.soft_delete {
+            quote!(Box::new(#schema::deleted_at.is_null()))
+        } else {
+            quote!(Box::new(diesel::dsl::sql::<diesel::sql_types::Bool>("TRUE")))
+        };
+
+        Ok(quote! {
+            impl #controller {
+                pub fn scope() -> Expr<#schema::table> {
+                    #scope
+                }
+            }
+        })
+    }
+}
+
+/// Whether the model is copied and prepared before it is written, which requires it to be `Clone`.
+pub fn prepares(input: &Input) -> bool {
+    input.parsed_struct.fields.iter().any(|field| {
+        !field.normalize().is_empty()
+            || field.slug_from().is_some()
+            || field.hasher().is_some()
+            || field.encrypted()
+    })
+}
+
+/// Normalizes a copy of the model, validates it, then hashes and encrypts its fields.
+pub struct PrepareModel;
+
+impl<'i> Builder<'i> for PrepareModel {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if !prepares(input) {
+            return Ok(quote!());
+        }
+
+        let fields = &input.parsed_struct.fields;
+        let model = input.parsed_struct.inner_model_name();
+        let controller = input.parsed_struct.controller_name();
+
+        let mut steps = NormalizeSteps.build(input)?;
+
+        if validates(input) {
+            steps.extend(quote!(model.validate()?;));
+        }
+
+        steps.extend(fields.iter().filter_map(hash_step));
+        steps.extend(fields.iter().filter_map(encrypt_step));
+
+        Ok(quote! {
+            impl #controller {
+                fn prepare_model(mut model: #model) -> Result<#model, ResourceError> {
+                    #steps
+                    Ok(model)
+                }
+            }
+        })
+    }
+}
+
+/// Shadows `model` with its prepared copy, or only validates it when nothing else is prepared.
+pub struct Prepare;
+
+impl<'i> Builder<'i> for Prepare {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if prepares(input) {
+            Ok(quote!(let model = &Self::prepare_model(model.clone())?;))
+        } else if validates(input) {
+            Ok(quote!(model.validate()?;))
+        } else {
+            Ok(quote!())
+        }
+    }
+}
+
+/// Shadows the `models` slice with its prepared copies, or only validates them when nothing else
+/// is prepared.
+pub struct PrepareAll;
+
+impl<'i> Builder<'i> for PrepareAll {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if prepares(input) {
+            Ok(quote! {
+                let models = models
+                    .iter()
+                    .cloned()
+                    .map(Self::prepare_model)
+                    .collect::<Result<Vec<_>, ResourceError>>()?;
+                let models = &models[..];
+            })
+        } else if validates(input) {
+            Ok(quote! {
+                for model in models {
+                    model.validate()?;
+                }
+            })
+        } else {
+            Ok(quote!())
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use crate::field::Field;
+
+/// Replaces the plaintext of an `#[encrypted]` field of `model` with its ciphertext.
+pub fn encrypt_step(field: &Field) -> Option<proc_macro2::TokenStream> {
+    if !field.encrypted() {
+        return None;
+    }
+
+    let name = &field.name;
+
+    if field.is_option() {
+        Some(
+            quote!(model.#name = model.#name.as_ref().map(|value| encrypt_value(value)).transpose()?;),
+        )
+    } else {
+        Some(quote!(model.#name = encrypt_value(&model.#name)?;))
+    }
+}
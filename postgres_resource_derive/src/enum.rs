@@ -0,0 +1,182 @@
+use proc_macro2::Span;
+use syn::{
+    parse::{Parse, ParseStream, Result},
+    punctuated::Punctuated,
+    Attribute, Error, Ident, Lit, LitStr,
+    Meta::*,
+    MetaNameValue, Path, Visibility,
+};
+
+use crate::{attr::Attrs, IdentExt};
+
+/// The derives generated for every enum, which are not repeated from the user's `#[derive]`.
+const DERIVES: &[&str] = &[
+    "Serialize",
+    "Deserialize",
+    "FromSqlRow",
+    "AsExpression",
+    "Clone",
+    "Copy",
+    "Debug",
+    "PartialEq",
+    "Eq",
+    "Hash",
+];
+
+#[derive(Debug)]
+pub struct Enum {
+    pub attrs: Vec<Attribute>,
+    pub derives: Vec<Path>,
+    pub type_name: Option<LitStr>,
+    pub vis: Visibility,
+    pub ident: Ident,
+    pub variants: Punctuated<Ident, Token![,]>,
+}
+
+impl Parse for Enum {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attrs = Vec::new();
+        let mut derives = Vec::new();
+        let mut type_name = None;
+
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path.is_ident("type_name") {
+                match attr.parse_meta()? {
+                    NameValue(MetaNameValue { lit: Lit::Str(lit_str), .. }) => {
+                        type_name = Some(lit_str)
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            attr.bracket_token.span,
+                            "expected #[type_name = \"...\"]",
+                        ))
+                    }
+                }
+            } else if attr.path.is_ident("derive") {
+                derives.extend(Attrs::parse_derive(&attr)?);
+            } else {
+                attrs.push(attr);
+            }
+        }
+
+        let content;
+        let vis = input.parse()?;
+        let _: Token![enum] = input.parse()?;
+        let ident = input.parse()?;
+        let _ = braced!(content in input);
+        let variants = content.parse_terminated(Ident::parse)?;
+        Ok(Enum { attrs, derives, type_name, vis, ident, variants })
+    }
+}
+
+impl Enum {
+    pub fn sql_type_name(&self) -> Ident {
+        self.ident.append("Type")
+    }
+
+    fn type_name(&self) -> LitStr {
+        match self.type_name {
+            Some(ref type_name) => type_name.clone(),
+            None => LitStr::new(&self.ident.snake_case().to_string(), self.ident.span()),
+        }
+    }
+
+    /// The generated derives, followed by the user's derives that aren't generated already.
+    fn derives(&self) -> Vec<Path> {
+        let mut derives = DERIVES
+            .iter()
+            .map(|derive| Ident::new(derive, Span::call_site()).into())
+            .collect::<Vec<Path>>();
+
+        self.derives.iter().for_each(|derive| {
+            let name = derive.segments.last().map(|pair| pair.value().ident.to_string());
+            if !DERIVES.iter().any(|generated| name.as_deref() == Some(*generated)) {
+                derives.push(derive.clone());
+            }
+        });
+
+        derives
+    }
+
+    fn labels(&self) -> Vec<LitStr> {
+        self.variants
+            .iter()
+            .map(|variant| LitStr::new(&variant.snake_case().to_string(), variant.span()))
+            .collect()
+    }
+
+    pub fn gen(&self) -> proc_macro2::TokenStream {
+        let attrs = &self.attrs;
+        let vis = &self.vis;
+        let ident = &self.ident;
+        let derives = self.derives();
+        let sql_type = self.sql_type_name();
+        let sql_type_literal = LitStr::new(&sql_type.to_string(), sql_type.span());
+        let type_name = self.type_name();
+
+        let variants = &self.variants.iter().collect::<Vec<_>>();
+        let labels = self.labels();
+        let bytes = labels
+            .iter()
+            .map(|label| syn::LitByteStr::new(label.value().as_bytes(), label.span()))
+            .collect::<Vec<_>>();
+
+        let quoted = labels.iter().map(|label| format!("'{}'", label.value())).collect::<Vec<_>>();
+        let create_type =
+            format!("CREATE TYPE {} AS ENUM ({})", type_name.value(), quoted.join(", "));
+        let drop_type = format!("DROP TYPE {}", type_name.value());
+
+        let to_sql = variants
+            .iter()
+            .zip(&bytes)
+            .map(|(variant, bytes)| quote!(#ident::#variant => out.write_all(#bytes)?));
+        let from_sql = variants
+            .iter()
+            .zip(&bytes)
+            .map(|(variant, bytes)| quote!(Some(#bytes) => Ok(#ident::#variant)));
+
+        quote! {
+            #[derive(SqlType, QueryId)]
+            #[postgres(type_name = #type_name)]
+            #vis struct #sql_type;
+
+            #(#attrs)*
+            #[derive(#(#derives),*)]
+            #[sql_type = #sql_type_literal]
+            #vis enum #ident {
+                #(#[serde(rename = #labels)] #variants,)*
+            }
+
+            impl #ident {
+                pub const CREATE_TYPE: &'static str = #create_type;
+                pub const DROP_TYPE: &'static str = #drop_type;
+            }
+
+            impl ResourceEnum for #ident {
+                const TYPE_NAME: &'static str = #type_name;
+            }
+
+            impl diesel::serialize::ToSql<#sql_type, diesel::pg::Pg> for #ident {
+                fn to_sql<W: std::io::Write>(
+                    &self,
+                    out: &mut diesel::serialize::Output<W, diesel::pg::Pg>,
+                ) -> diesel::serialize::Result {
+                    match *self {
+                        #(#to_sql,)*
+                    }
+                    Ok(diesel::serialize::IsNull::No)
+                }
+            }
+
+            impl diesel::deserialize::FromSql<#sql_type, diesel::pg::Pg> for #ident {
+                fn from_sql(bytes: Option<&[u8]>) -> diesel::deserialize::Result<Self> {
+                    match bytes {
+                        #(#from_sql,)*
+                        Some(_) => Err(concat!("Unrecognized variant for ", #type_name).into()),
+                        None => Err("Unexpected null for non-null column".into()),
+                    }
+                }
+            }
+        }
+    }
+}
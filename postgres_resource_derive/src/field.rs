@@ -1,62 +1,448 @@
+use crate::{validate::Validation, IdentExt};
+
 use syn::{
-    parse::{Parse, ParseStream, Result},
-    Attribute, Ident,
+    parse::{Parse, ParseStream, Parser, Result},
+    Attribute, Error, GenericArgument, Ident, Lit, LitStr,
+    Meta::*,
+    MetaNameValue, NestedMeta, PathArguments, PathSegment, Type,
 };
 
 #[derive(Debug, PartialEq)]
 enum FieldAttr {
     Optional,
-    ForeignKey,
+    ForeignKey(Option<Ident>),
+    Version,
+    Jsonb,
+    SqlType(LitStr),
+    Column(LitStr),
+    Generated(Option<LitStr>),
+    ReadOnly(Option<LitStr>),
+    Sensitive,
+    Hashed(Ident),
+    Encrypted,
+    Validate(Vec<Validation>),
+    Normalize(Vec<Ident>),
+    Slug(Ident),
+    Default(DefaultExpr),
+    DbDefault(Option<LitStr>),
+}
+
+/// The tokens of the expression of `#[default = expr]`.
+#[derive(Debug)]
+struct DefaultExpr(proc_macro2::TokenStream);
+
+impl PartialEq for DefaultExpr {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_string() == other.0.to_string()
+    }
 }
 
 #[derive(Debug)]
 pub struct Field {
-//    belongs_to: Option<proc_macro2::TokenStream>,
+    //    belongs_to: Option<proc_macro2::TokenStream>,
     attr: Vec<FieldAttr>,
+    /// Attributes which are not resource attributes, forwarded to the generated field.
+    forward: Vec<Attribute>,
     pub name: Ident,
-    ty: Ident,
+    ty: Type,
 }
 
 impl Parse for Field {
     fn parse(input: ParseStream) -> Result<Self> {
-        let attr = Self::parse_attr(&input)?;
+        let (attr, forward) = Self::parse_attr(&input)?;
         let name: Ident = input.parse()?;
         let _: Token![:] = input.parse()?;
         let ty = input.parse()?;
+        let field = Field { attr, forward, name, ty };
 
-        Ok(Field { attr, name, ty })
+        if field.hasher().is_some() && !field.is_type("Password") {
+            return Err(Error::new_spanned(&field.ty, "#[hashed] fields must be a `Password`"));
+        }
+
+        Ok(field)
     }
 }
 
 impl Field {
-    fn parse_attr(input: &ParseStream) -> Result<Vec<FieldAttr>> {
+    fn parse_attr(input: &ParseStream) -> Result<(Vec<FieldAttr>, Vec<Attribute>)> {
         let mut result = Vec::new();
+        let mut forward = Vec::new();
         if let Some(attrs) = input.call(Attribute::parse_outer).ok() {
-            attrs.iter().for_each(|attr| {
+            for attr in attrs {
                 let ident = &attr.path.segments[0].ident;
 
                 match &ident.to_string()[..] {
                     "optional" => result.push(FieldAttr::Optional),
-                    "fk" => result.push(FieldAttr::ForeignKey),
-                    _ => panic!("Invalid attribute for field"),
+                    "fk" => result.push(FieldAttr::ForeignKey(Self::parse_fk(&attr)?)),
+                    "version" => result.push(FieldAttr::Version),
+                    "jsonb" => result.push(FieldAttr::Jsonb),
+                    "generated" => {
+                        result.push(FieldAttr::Generated(Self::parse_column_default(&attr)?))
+                    }
+                    "readonly" => {
+                        result.push(FieldAttr::ReadOnly(Self::parse_column_default(&attr)?))
+                    }
+                    "sensitive" => result.push(FieldAttr::Sensitive),
+                    "encrypted" => result.push(FieldAttr::Encrypted),
+                    "normalize" => result.push(FieldAttr::Normalize(Self::parse_normalize(&attr)?)),
+                    "default" => result.push(FieldAttr::Default(Self::parse_default(&attr)?)),
+                    "db_default" => {
+                        result.push(FieldAttr::DbDefault(Self::parse_column_default(&attr)?))
+                    }
+                    "slug" => result.push(FieldAttr::Slug(Self::parse_slug(&attr)?)),
+                    "validate" => result.push(FieldAttr::Validate(Validation::parse_all(&attr)?)),
+                    "hashed" => result.push(FieldAttr::Hashed(Self::parse_hashed(&attr)?)),
+                    "sql_type" => {
+                        result.push(FieldAttr::SqlType(Self::parse_name_value(&attr, "sql_type")?))
+                    }
+                    "column" => {
+                        result.push(FieldAttr::Column(Self::parse_name_value(&attr, "column")?))
+                    }
+                    _ => forward.push(attr),
                 }
-            });
+            }
+        }
+        Ok((result, forward))
+    }
+
+    fn parse_fk(attr: &Attribute) -> Result<Option<Ident>> {
+        match attr.parse_meta()? {
+            Word(_) => Ok(None),
+            List(ref list) if list.nested.len() == 1 => match list.nested[0] {
+                NestedMeta::Meta(Word(ref target)) => Ok(Some(target.clone())),
+                _ => Err(Error::new(attr.bracket_token.span, "expected #[fk(Resource)]")),
+            },
+            _ => Err(Error::new(attr.bracket_token.span, "expected #[fk(Resource)]")),
+        }
+    }
+
+    fn parse_hashed(attr: &Attribute) -> Result<Ident> {
+        match attr.parse_meta()? {
+            List(ref list) if list.nested.len() == 1 => match list.nested[0] {
+                NestedMeta::Meta(Word(ref scheme)) => Ok(scheme.clone()),
+                _ => Err(Error::new(attr.bracket_token.span, "expected #[hashed(scheme)]")),
+            },
+            _ => Err(Error::new(attr.bracket_token.span, "expected #[hashed(scheme)]")),
+        }
+    }
+
+    fn parse_normalize(attr: &Attribute) -> Result<Vec<Ident>> {
+        let error = || {
+            Error::new(attr.bracket_token.span, "expected #[normalize(trim, lowercase, uppercase)]")
+        };
+
+        match attr.parse_meta()? {
+            List(list) => list
+                .nested
+                .iter()
+                .map(|nested| match nested {
+                    NestedMeta::Meta(Word(step))
+                        if step == "trim" || step == "lowercase" || step == "uppercase" =>
+                    {
+                        Ok(step.clone())
+                    }
+                    _ => Err(error()),
+                })
+                .collect(),
+            _ => Err(error()),
+        }
+    }
+
+    fn parse_default(attr: &Attribute) -> Result<DefaultExpr> {
+        let parser = |input: ParseStream| {
+            let _: Token![=] = input.parse()?;
+            input.parse::<proc_macro2::TokenStream>()
+        };
+
+        match parser.parse2(attr.tts.clone()) {
+            Ok(ref expr) if !expr.is_empty() => Ok(DefaultExpr(expr.clone())),
+            _ => Err(Error::new(attr.bracket_token.span, "expected #[default = expr]")),
+        }
+    }
+
+    /// Parses the SQL expression of `#[generated(default = "...")]`, which is optional.
+    fn parse_column_default(attr: &Attribute) -> Result<Option<LitStr>> {
+        let ident = &attr.path.segments[0].ident;
+        let error = || {
+            Error::new(
+                attr.bracket_token.span,
+                format!("expected #[{}] or #[{}(default = \"...\")]", ident, ident),
+            )
+        };
+
+        match attr.parse_meta()? {
+            Word(_) => Ok(None),
+            List(ref list) if list.nested.len() == 1 => match list.nested[0] {
+                NestedMeta::Meta(NameValue(MetaNameValue {
+                    ref ident,
+                    lit: Lit::Str(ref default),
+                    ..
+                })) if ident == "default" => Ok(Some(default.clone())),
+                _ => Err(error()),
+            },
+            _ => Err(error()),
+        }
+    }
+
+    fn parse_slug(attr: &Attribute) -> Result<Ident> {
+        let error = || Error::new(attr.bracket_token.span, "expected #[slug(from = \"field\")]");
+
+        match attr.parse_meta()? {
+            List(ref list) if list.nested.len() == 1 => match list.nested[0] {
+                NestedMeta::Meta(NameValue(MetaNameValue {
+                    ref ident,
+                    lit: Lit::Str(ref from),
+                    ..
+                })) if ident == "from" => Ok(Ident::new(&from.value(), from.span())),
+                _ => Err(error()),
+            },
+            _ => Err(error()),
+        }
+    }
+
+    fn parse_name_value(attr: &Attribute, name: &str) -> Result<LitStr> {
+        match attr.parse_meta()? {
+            NameValue(MetaNameValue { lit: Lit::Str(lit_str), .. }) => Ok(lit_str),
+            _ => {
+                Err(Error::new(attr.bracket_token.span, format!("expected #[{} = \"...\"]", name)))
+            }
         }
-        Ok(result)
     }
 
     pub fn ty(&self) -> proc_macro2::TokenStream {
+        let ty = self.value_ty();
+
+        if self.is_option() {
+            quote!(Option<#ty>)
+        } else {
+            ty
+        }
+    }
+
+    /// The type of the field, before wrapping `#[optional]` and `#[db_default]` fields in `Option`.
+    /// `#[jsonb]` fields are wrapped in `Json`, which diesel reads and writes as `jsonb`.
+    pub fn value_ty(&self) -> proc_macro2::TokenStream {
         let ty = &self.ty;
-        let mut ty_tokens = quote!(#ty);
 
-        if self.attr.contains(&FieldAttr::Optional) {
-            ty_tokens = quote!(Option<#ty>);
+        if self.jsonb() {
+            quote!(Json<#ty>)
+        } else {
+            quote!(#ty)
+        }
+    }
+
+    /// The type of the field in the `Queryable` row. Fields with a `#[sql_type]` are read
+    /// through `FromSqlValue`, so that their type doesn't need to derive `FromSqlRow`, and
+    /// `#[encrypted]` fields through `Encrypted`, which decrypts them.
+    pub fn row_ty(&self) -> proc_macro2::TokenStream {
+        let ty = if self.encrypted() {
+            quote!(Encrypted)
+        } else if self.sql_type().is_some() {
+            let ty = self.value_ty();
+            quote!(FromSqlValue<#ty>)
+        } else {
+            self.value_ty()
+        };
+
+        if self.optional() {
+            quote!(Option<#ty>)
+        } else {
+            ty
+        }
+    }
+
+    /// Converts `value`, of type `row_ty`, into the type of the field, wrapping `#[db_default]`
+    /// fields in `Some`.
+    pub fn from_row(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let value = match (self.encrypted() || self.sql_type().is_some(), self.optional()) {
+            (false, _) => value,
+            (true, false) => quote!(#value.0),
+            (true, true) => quote!(#value.map(|value| value.0)),
+        };
+
+        if self.is_option() && !self.optional() {
+            quote!(Some(#value))
+        } else {
+            value
         }
+    }
+
+    pub fn declared_ty(&self) -> &Type {
+        &self.ty
+    }
 
-        ty_tokens
+    pub fn sql_type(&self) -> Option<&LitStr> {
+        self.attr.iter().find_map(|attr| match attr {
+            FieldAttr::SqlType(sql_type) => Some(sql_type),
+            _ => None,
+        })
+    }
+
+    /// The column of the field in the schema module, given by `#[column = "..."]` or the field name.
+    pub fn column(&self) -> Ident {
+        self.attr
+            .iter()
+            .find_map(|attr| match attr {
+                FieldAttr::Column(column) => Some(Ident::new(&column.value(), column.span())),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.name.clone())
+    }
+
+    /// The attributes of the generated field: the forwarded attributes, `#[column_name]` for
+    /// renamed columns and `#[serde(skip_serializing)]` for sensitive fields.
+    pub fn attrs(&self) -> proc_macro2::TokenStream {
+        let forward = &self.forward;
+        let column = self.column();
+
+        let column_name = if column == self.name {
+            quote!()
+        } else {
+            let literal = LitStr::new(&column.to_string(), column.span());
+            quote!(#[column_name = #literal])
+        };
+
+        let skip_serializing =
+            if self.sensitive() { quote!(#[serde(skip_serializing)]) } else { quote!() };
+
+        quote!(#(#forward)* #column_name #skip_serializing)
+    }
+
+    pub fn optional(&self) -> bool {
+        self.attr.contains(&FieldAttr::Optional)
+    }
+
+    /// Whether the column is left to its SQL `DEFAULT` when the field is `None` on insert.
+    pub fn db_default(&self) -> bool {
+        self.attr.iter().any(|attr| matches!(attr, FieldAttr::DbDefault(_)))
+    }
+
+    /// Whether the field is an `Option` in the generated structs.
+    pub fn is_option(&self) -> bool {
+        self.optional() || self.db_default()
+    }
+
+    /// The expression of `#[default = expr]`.
+    pub fn default(&self) -> Option<&proc_macro2::TokenStream> {
+        self.attr.iter().find_map(|attr| match attr {
+            FieldAttr::Default(expr) => Some(&expr.0),
+            _ => None,
+        })
     }
 
     pub fn fk(&self) -> bool {
-        self.attr.contains(&FieldAttr::ForeignKey)
+        self.attr.iter().any(|attr| match attr {
+            FieldAttr::ForeignKey(_) => true,
+            _ => false,
+        })
+    }
+
+    /// The resource referenced by `#[fk(Resource)]`.
+    pub fn fk_target(&self) -> Option<&Ident> {
+        self.attr.iter().find_map(|attr| match attr {
+            FieldAttr::ForeignKey(target) => target.as_ref(),
+            _ => None,
+        })
+    }
+
+    /// The element type of a `Vec<T>` field, stored in an array column.
+    pub fn array_element(&self) -> Option<&Type> {
+        let segment = self.last_segment().filter(|segment| segment.ident == "Vec")?;
+
+        match segment.arguments {
+            PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => match args.args[0] {
+                GenericArgument::Type(ref element) => Some(element),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether the declared type is named `name`, ignoring its path.
+    pub fn is_type(&self, name: &str) -> bool {
+        self.last_segment().map(|segment| segment.ident == name).unwrap_or(false)
+    }
+
+    fn last_segment(&self) -> Option<&PathSegment> {
+        match self.ty {
+            Type::Path(ref path) => path.path.segments.last().map(|pair| pair.into_value()),
+            _ => None,
+        }
+    }
+
+    pub fn jsonb(&self) -> bool {
+        self.attr.contains(&FieldAttr::Jsonb)
+    }
+
+    pub fn version(&self) -> bool {
+        self.attr.contains(&FieldAttr::Version)
+    }
+
+    pub fn sensitive(&self) -> bool {
+        self.attr.contains(&FieldAttr::Sensitive)
+    }
+
+    /// The `PasswordHasher` named by `#[hashed(scheme)]`, such as `Argon2` for `argon2`.
+    pub fn hasher(&self) -> Option<Ident> {
+        self.attr.iter().find_map(|attr| match attr {
+            FieldAttr::Hashed(scheme) => Some(scheme.camel_case()),
+            _ => None,
+        })
+    }
+
+    pub fn encrypted(&self) -> bool {
+        self.attr.contains(&FieldAttr::Encrypted)
+    }
+
+    /// The steps of `#[normalize(...)]`, as `Normalize` variants.
+    pub fn normalize(&self) -> Vec<Ident> {
+        self.attr
+            .iter()
+            .flat_map(|attr| match attr {
+                FieldAttr::Normalize(steps) => steps.iter().map(IdentExt::camel_case).collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// The field a `#[slug(from = "...")]` field is derived from.
+    pub fn slug_from(&self) -> Option<&Ident> {
+        self.attr.iter().find_map(|attr| match attr {
+            FieldAttr::Slug(from) => Some(from),
+            _ => None,
+        })
+    }
+
+    /// The checks of all `#[validate(...)]` attributes of the field.
+    pub fn validations(&self) -> Vec<&Validation> {
+        self.attr
+            .iter()
+            .flat_map(|attr| match attr {
+                FieldAttr::Validate(validations) => validations.iter().collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Whether the column is written by the database only, through a default or a trigger.
+    pub fn read_only(&self) -> bool {
+        self.attr
+            .iter()
+            .any(|attr| matches!(attr, FieldAttr::Generated(_) | FieldAttr::ReadOnly(_)))
+    }
+
+    /// The SQL `DEFAULT` of the column, given to `#[generated]`, `#[readonly]` or `#[db_default]`.
+    pub fn column_default(&self) -> Option<&LitStr> {
+        self.attr.iter().find_map(|attr| match attr {
+            FieldAttr::Generated(default)
+            | FieldAttr::ReadOnly(default)
+            | FieldAttr::DbDefault(default) => default.as_ref(),
+            _ => None,
+        })
+    }
+
+    /// Whether the field belongs to the inner model rather than the `WithId` struct.
+    pub fn inner(&self) -> bool {
+        !self.fk() && !self.version() && !self.read_only()
     }
 }
@@ -0,0 +1,105 @@
+use crate::{builder::*, IdentExt};
+
+use syn::parse::Result;
+
+pub struct Filters;
+
+impl<'i> Builder<'i> for Filters {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let filter = input.parsed_struct.filter_name();
+
+        let mut filters = Vec::new();
+
+        input.parsed_struct.fields.iter().for_each(|field| {
+            let name = &field.name;
+            let column = field.column();
+
+            if field.jsonb() {
+                let contains = name.append("_contains");
+                let has_key = name.append("_has_key");
+
+                filters.push(quote! {
+                    pub fn #contains<T>(value: T) -> Expr<#schema::table>
+                    where
+                        T: serde::Serialize + std::fmt::Debug + 'static,
+                    {
+                        Box::new(#schema::#column.contains_json(Json(value)))
+                    }
+
+                    pub fn #has_key(key: &str) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.has_key(key.to_string()))
+                    }
+                });
+            }
+
+            // Filters on nullable arrays are not supported by diesel
+            if let (Some(element), false) = (field.array_element(), field.optional()) {
+                let ty = field.ty();
+                let contains = name.append("_contains");
+                let overlaps = name.append("_overlaps");
+                let any = name.append("_any");
+
+                filters.push(quote! {
+                    pub fn #contains(values: #ty) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.contains(values))
+                    }
+
+                    pub fn #overlaps(values: #ty) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.overlaps_with(values))
+                    }
+
+                    pub fn #any(value: #element) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.any_eq(value))
+                    }
+                });
+            }
+
+            if field.is_type("TstzRange") && !field.optional() {
+                let ty = field.ty();
+                let contains = name.append("_contains");
+                let overlaps = name.append("_overlaps");
+
+                filters.push(quote! {
+                    pub fn #contains(value: chrono::DateTime<chrono::Utc>) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.contains_value(value))
+                    }
+
+                    pub fn #overlaps(range: #ty) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.overlaps_range(range))
+                    }
+                });
+            }
+
+            if field.is_type("IpNetwork") {
+                let ty = field.ty();
+                let within = name.append("_within");
+                let contains = name.append("_contains");
+
+                filters.push(quote! {
+                    pub fn #within(network: #ty) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.within_network(network))
+                    }
+
+                    pub fn #contains(network: #ty) -> Expr<#schema::table> {
+                        Box::new(#schema::#column.contains_network(network))
+                    }
+                });
+            }
+        });
+
+        if filters.is_empty() {
+            return Ok(quote!());
+        }
+
+        let vis = &input.parsed_struct.vis;
+
+        Ok(quote! {
+            #vis struct #filter;
+
+            impl #filter {
+                #(#filters)*
+            }
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{builder::*, field::Field, IdentExt};
+
+use syn::parse::Result;
+
+/// Replaces a `Password::Plain` in a `#[hashed]` field of `model` with its hash.
+pub fn hash_step(field: &Field) -> Option<proc_macro2::TokenStream> {
+    let hasher = field.hasher()?;
+    let name = &field.name;
+
+    if field.is_option() {
+        Some(quote! {
+            model.#name = model.#name.map(Password::into_hashed::<#hasher>).transpose()?;
+        })
+    } else {
+        Some(quote! {
+            model.#name = model.#name.into_hashed::<#hasher>()?;
+        })
+    }
+}
+
+pub struct PasswordVerifiers;
+
+impl<'i> Builder<'i> for PasswordVerifiers {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let controller = input.parsed_struct.controller_name();
+
+        let mut verifiers = Vec::new();
+
+        input.parsed_struct.fields.iter().for_each(|field| {
+            if let Some(hasher) = field.hasher() {
+                let name = &field.name;
+                let method = name.prepend("verify_");
+
+                let verify = if field.is_option() {
+                    quote! {
+                        match model.inner.#name {
+                            Some(ref password) => password.verify::<#hasher>(candidate),
+                            None => Ok(false),
+                        }
+                    }
+                } else {
+                    quote!(model.inner.#name.verify::<#hasher>(candidate))
+                };
+
+                verifiers.push(quote! {
+                    pub fn #method(&self, by: Expr<#schema::table>, candidate: &str) -> Result<bool, ResourceError> {
+                        let model = self.get_one(by)?;
+                        #verify
+                    }
+                });
+            }
+        });
+
+        if verifiers.is_empty() {
+            return Ok(quote!());
+        }
+
+        Ok(quote! {
+            impl #controller {
+                #(#verifiers)*
+            }
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{builder::*, soft_delete::DeleteQuery};
+
+use proc_macro2::Span;
+use syn::{parse::Result, Ident};
+
+/// Whether the controller runs its `ResourceHooks` around writes, which requires the model to be
+/// `Clone`.
+pub fn hooks(input: &Input) -> bool {
+    input.parsed_struct.attrs.hooks
+}
+
+/// Shadows `model` with a copy passed to the `before` hook, which may change it.
+pub fn before_hook(before: &str) -> proc_macro2::TokenStream {
+    let before = Ident::new(before, Span::call_site());
+
+    quote! {
+        let mut model = model.clone();
+        self.#before(connection, &mut model)?;
+        let model = &model;
+    }
+}
+
+/// Runs `write`, a query of the prepared `model` on `connection`, in a transaction between the
+/// `before` and `after` hooks. The `before` hook gets a copy of the model, prepared afterwards.
+pub fn hooked_write(
+    input: &Input,
+    before: &str,
+    after: &str,
+    write: proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    let connection = DatabaseConnection.build(input)?;
+    let prepare = Prepare.build(input)?;
+    let before = before_hook(before);
+    let after = Ident::new(after, Span::call_site());
+
+    Ok(quote! {
+        let connection = #connection;
+        connection.transaction::<_, ResourceError, _>(|| {
+            #before
+            #prepare
+            let written = #write?;
+            self.#after(connection, &written)?;
+            Ok(written)
+        })
+    })
+}
+
+/// Loads the rows matching `by` and `filter` on `connection` as `models` and passes them to the
+/// `before` hook, then narrows `by` down to their ids, so that the write following it only
+/// touches the rows the hook was given.
+pub fn hooked_rows(
+    input: &Input,
+    filter: proc_macro2::TokenStream,
+    before: &str,
+) -> Result<proc_macro2::TokenStream> {
+    let schema = Schema.build(input)?;
+    let before = Ident::new(before, Span::call_site());
+
+    Ok(quote! {
+        let models = #schema::table
+            .filter(by)
+            #filter
+            .get_results::<Self::ModelWithId>(connection)?;
+        self.#before(connection, &models)?;
+
+        let ids = models.iter().map(|model| model.id).collect::<Vec<_>>();
+        let by: Expr<#schema::table> = Box::new(#schema::id.eq_any(ids));
+    })
+}
+
+pub struct CreateBody;
+
+impl<'i> Builder<'i> for CreateBody {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let insert_values = InsertValues.build(input)?;
+
+        if hooks(input) {
+            return hooked_write(
+                input,
+                "before_create",
+                "after_create",
+                quote! {
+                    insert_into(#schema::table)
+                        .values(#insert_values)
+                        .get_result::<Self::ModelWithId>(connection)
+                },
+            );
+        }
+
+        let connection = DatabaseConnection.build(input)?;
+        let prepare = Prepare.build(input)?;
+
+        Ok(quote! {
+            #prepare
+            Ok(insert_into(#schema::table)
+               .values(#insert_values)
+               .get_result(#connection)?)
+        })
+    }
+}
+
+/// Implements `ResourceUpdate`, updating the matching rows. Versioned resources are only updated
+/// through `update_versioned`, as an update without the version read would overwrite concurrent
+/// changes.
+pub struct UpdateController;
+
+impl<'i> Builder<'i> for UpdateController {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if input.parsed_struct.version_field().is_some() {
+            return Ok(quote!());
+        }
+
+        let schema = Schema.build(input)?;
+        let changeset = Changeset.build(input)?;
+        let controller = input.parsed_struct.controller_name();
+
+        let body = if hooks(input) {
+            hooked_write(
+                input,
+                "before_update",
+                "after_update",
+                quote! {
+                    update(#schema::table)
+                        .filter(by)
+                        .set(#changeset)
+                        .get_result::<Self::ModelWithId>(connection)
+                },
+            )?
+        } else {
+            let connection = DatabaseConnection.build(input)?;
+            let prepare = Prepare.build(input)?;
+
+            quote! {
+                #prepare
+                Ok(update(#schema::table)
+                   .filter(by)
+                   .set(#changeset)
+                   .get_result::<Self::ModelWithId>(#connection)?)
+            }
+        };
+
+        Ok(quote! {
+            impl ResourceUpdate for #controller {
+                fn update(&self, model: &Self::Model, by: Expr<#schema::table>) -> Result<Self::ModelWithId, ResourceError> {
+                    #body
+                }
+            }
+        })
+    }
+}
+
+/// Deletes the matching rows. With hooks, the rows are loaded first and only those passed to the
+/// hooks are deleted.
+pub struct DeleteBody;
+
+impl<'i> Builder<'i> for DeleteBody {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let connection = DatabaseConnection.build(input)?;
+        let delete_query = DeleteQuery.build(input)?;
+
+        if !hooks(input) {
+            return Ok(quote! {
+                let connection = #connection;
+                Ok(#delete_query?)
+            });
+        }
+
+        let scope = Scope.build(input)?;
+        let rows = hooked_rows(input, scope, "before_delete")?;
+
+        Ok(quote! {
+            let connection = #connection;
+            connection.transaction::<_, ResourceError, _>(|| {
+                #rows
+                let deleted = #delete_query?;
+                self.after_delete(connection, &models)?;
+                Ok(deleted)
+            })
+        })
+    }
+}
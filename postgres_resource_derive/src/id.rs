@@ -0,0 +1,47 @@
+use crate::builder::*;
+
+use syn::parse::Result;
+
+/// The `XId` newtype of the `id` column, so that ids of different resources can't be mixed up.
+pub struct TypedId;
+
+impl<'i> Builder<'i> for TypedId {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let vis = &input.parsed_struct.vis;
+        let id = input.parsed_struct.id_name();
+
+        Ok(quote! {
+            #[derive(Serialize, Deserialize, FromSqlRow, AsExpression, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            #[sql_type = "diesel::sql_types::Integer"]
+            #[serde(transparent)]
+            #vis struct #id(pub i32);
+
+            impl diesel::serialize::ToSql<diesel::sql_types::Integer, diesel::pg::Pg> for #id {
+                fn to_sql<W: std::io::Write>(
+                    &self,
+                    out: &mut diesel::serialize::Output<W, diesel::pg::Pg>,
+                ) -> diesel::serialize::Result {
+                    diesel::serialize::ToSql::<diesel::sql_types::Integer, diesel::pg::Pg>::to_sql(&self.0, out)
+                }
+            }
+
+            impl diesel::deserialize::FromSql<diesel::sql_types::Integer, diesel::pg::Pg> for #id {
+                fn from_sql(bytes: Option<&[u8]>) -> diesel::deserialize::Result<Self> {
+                    diesel::deserialize::FromSql::<diesel::sql_types::Integer, diesel::pg::Pg>::from_sql(bytes).map(#id)
+                }
+            }
+
+            impl From<#id> for i32 {
+                fn from(id: #id) -> i32 {
+                    id.0
+                }
+            }
+
+            impl std::fmt::Display for #id {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+        })
+    }
+}
@@ -1,4 +1,4 @@
-#![recursion_limit = "128"]
+#![recursion_limit = "256"]
 
 #[macro_use]
 extern crate syn;
@@ -10,14 +10,32 @@ extern crate quote;
 
 type TokenStream = proc_macro::TokenStream;
 
+mod association;
 mod attr;
 mod builder;
+mod encrypted;
+mod r#enum;
 mod field;
-mod r#struct;
+mod filter;
+mod hashed;
+mod hooks;
+mod id;
+mod migration;
 mod model;
+mod model_builder;
+mod normalize;
 mod queryable;
+mod sensitive;
+mod soft_delete;
+mod r#struct;
+mod validate;
+mod version;
 
-use crate::{model::*, queryable::*, builder::*, r#struct::*};
+use crate::{
+    association::*, builder::*, filter::*, hashed::*, hooks::*, id::*, migration::*, model::*,
+    model_builder::*, normalize::*, queryable::*, r#enum::*, r#struct::*, sensitive::*,
+    soft_delete::*, validate::*, version::*,
+};
 
 use proc_macro2::Span;
 use syn::{parse::Result, Ident};
@@ -26,6 +44,7 @@ use heck::{CamelCase, SnakeCase};
 
 trait IdentExt {
     fn append(&self, string: &str) -> Ident;
+    fn prepend(&self, string: &str) -> Ident;
     fn camel_case(&self) -> Ident;
     fn snake_case(&self) -> Ident;
 }
@@ -35,6 +54,10 @@ impl IdentExt for syn::Ident {
         Ident::new(&format!("{}{}", self, string), self.span())
     }
 
+    fn prepend(&self, string: &str) -> Ident {
+        Ident::new(&format!("{}{}", string, self), self.span())
+    }
+
     fn camel_case(&self) -> Ident {
         Ident::new(&self.to_string().to_camel_case(), self.span())
     }
@@ -52,30 +75,92 @@ impl Input {
     fn gen_model(&self) -> Result<proc_macro2::TokenStream> {
         let table_macro = TableMacro.build(&self)?;
 
+        let belongs_to = BelongsTo.build(&self)?;
+
+        let mut model_with_id_derives = vec![
+            "Serialize",
+            "Deserialize",
+            "FromSqlRow",
+            "Associations",
+            "Identifiable",
+            "PartialEq",
+        ];
+        if !self.parsed_struct.sensitive(false) {
+            model_with_id_derives.push("Debug");
+        }
+        let model_with_id_derives = Derives(model_with_id_derives).build(&self)?;
+        let typed_id = TypedId.build(&self)?;
         let model_with_id = ModelWithId.build(&self)?;
+
+        let mut model_derives = vec![
+            "Serialize",
+            "Deserialize",
+            "FromSqlRow",
+            "Insertable",
+            "AsChangeset",
+            "PartialEq",
+        ];
+        if !self.parsed_struct.sensitive(true) {
+            model_derives.push("Debug");
+        }
+        if prepares(&self) || hooks(&self) {
+            model_derives.push("Clone");
+        }
+        let model_derives = Derives(model_derives).build(&self)?;
         let model = Model.build(&self)?;
+        let sensitive_debug = SensitiveDebug.build(&self)?;
+        let validations = Validations.build(&self)?;
+        let model_default = ModelDefault.build(&self)?;
+        let model_builder = ModelBuilder.build(&self)?;
 
         Ok(quote! {
-            #[derive(Serialize, Deserialize, FromSqlRow, Associations, Identifiable, Debug, PartialEq)]
+            #typed_id
+
+            #model_with_id_derives
+            #belongs_to
             #table_macro
             #model_with_id
 
-            #[derive(Serialize, Deserialize, FromSqlRow, Insertable, AsChangeset, Debug, PartialEq)]
+            #model_derives
             #table_macro
             #model
+
+            #sensitive_debug
+            #validations
+            #model_default
+            #model_builder
         })
     }
 
     fn gen_controller(&self) -> Result<proc_macro2::TokenStream> {
         let schema = Schema.build(&self)?;
         let connection = DatabaseConnection.build(&self)?;
+        let create = CreateBody.build(&self)?;
+        let update = UpdateController.build(&self)?;
+        let delete = DeleteBody.build(&self)?;
+        let prepare_model = PrepareModel.build(&self)?;
+        let scope = Scope.build(&self)?;
+        let scope_fn = ScopeFn.build(&self)?;
+        let soft_delete = SoftDeleteController.build(&self)?;
+        let versioned = VersionedController.build(&self)?;
+        let parent_loaders = ParentLoaders.build(&self)?;
+        let child_loaders = ChildLoaders.build(&self)?;
+        let many_to_many = ManyToManyRelations.build(&self)?;
+        let nested_writes = NestedWrites.build(&self)?;
+        let filters = Filters.build(&self)?;
+        let migration = Migration.build(&self)?;
+        let verifiers = PasswordVerifiers.build(&self)?;
+        let finders = NormalizedFinders.build(&self)?;
 
         let model = self.parsed_struct.inner_model_name();
         let model_with_id = self.parsed_struct.model_name_with_id();
         let controller = self.parsed_struct.controller_name();
+        let vis = &self.parsed_struct.vis;
+        let docs = &self.parsed_struct.attrs.docs;
 
         Ok(quote! {
-            pub struct #controller;
+            #(#docs)*
+            #vis struct #controller;
 
             impl ResourceDB for #controller {}
             impl Resource for #controller {
@@ -87,44 +172,169 @@ impl Input {
                 type ModelWithId = #model_with_id;
             }
             impl ResourceController for #controller {
-                fn create(&self, model: &Self::Model) -> Result<Self::ModelWithId, Error> {
-                    Ok(insert_into(#schema::table)
-                       .values(model)
-                       .get_result(#connection)?)
+                fn create(&self, model: &Self::Model) -> Result<Self::ModelWithId, ResourceError> {
+                    #create
                 }
 
-                fn get_one(&self, by: Expr<#schema::table>) -> Result<Self::ModelWithId, Error> {
+                fn get_one(&self, by: Expr<#schema::table>) -> Result<Self::ModelWithId, ResourceError> {
                     Ok(#schema::table
                        .filter(by)
+                       #scope
                        .get_result::<Self::ModelWithId>(#connection)?)
                 }
 
-                fn get_all(&self, by: Expr<#schema::table>) -> Result<Vec<Self::ModelWithId>, Error> {
+                fn get_all(&self, by: Expr<#schema::table>) -> Result<Vec<Self::ModelWithId>, ResourceError> {
                     Ok(#schema::table
                        .filter(by)
+                       #scope
                        .get_results::<Self::ModelWithId>(#connection)?)
                 }
 
-                fn update(&self, model: &Self::Model, by: Expr<#schema::table>) -> Result<Self::ModelWithId, Error> {
-                    Ok(update(#schema::table)
-                       .filter(by)
-                       .set(model)
-                       .get_result::<Self::ModelWithId>(#connection)?)
+                fn delete(&self, by: Expr<#schema::table>) -> Result<usize, ResourceError> {
+                    #delete
                 }
 
-                fn delete(&self, by: Expr<#schema::table>) -> Result<usize, Error> {
-                    Ok(delete(#schema::table).filter(by).execute(#connection)?)
+                fn count(&self, by: Expr<#schema::table>) -> Result<i64, ResourceError> {
+                    Ok(#schema::table
+                       .filter(by)
+                       #scope
+                       .count()
+                       .get_result::<i64>(#connection)?)
                 }
             }
+
+            #update
+            #scope_fn
+            #prepare_model
+            #soft_delete
+            #versioned
+            #parent_loaders
+            #child_loaders
+            #many_to_many
+            #nested_writes
+            #filters
+            #verifiers
+            #finders
+            #migration
         })
     }
 }
 
+///
+/// ### Resource attributes
+/// - `#[table = "..."]` sets the table name, defaulting to the pluralized struct name.
+/// - `#[env_var = "..."]` reads the database url from the given environment variable.
+/// - `#[timestamps]` adds `created_at` and `updated_at` (`chrono::NaiveDateTime`) columns,
+///   expected after the declared fields, to the `WithId` struct. Both are set on insert and
+///   `updated_at` is bumped on every update, including soft deletes and restores.
+/// - `#[soft_delete]` adds a nullable `deleted_at` column, after the timestamps, to the `WithId`
+///   struct. `delete` sets it instead of removing the row, `get_one`, `get_all` and `count` skip
+///   deleted rows, and `ResourceSoftDelete` is implemented for the controller. The filter is
+///   also `AccountController::scope()`, which the loaders of related resources apply.
+/// - `#[hooks]` makes every generated write call the `ResourceHooks` of the controller, which
+///   must be implemented, in the transaction of the write: `create`, `create_with_<children>` and
+///   `create_for_<parent>` call the create hooks, `update` and `update_versioned` the update
+///   hooks, `delete` and `purge` the delete hooks and `restore` the restore hooks. Before-hooks
+///   get a copy of the model to change before it is prepared, and `delete`, `purge` and `restore`
+///   only write the rows passed to their before-hook. `create_with_<children>` calls
+///   `after_create` once the children are inserted.
+/// - `#[has_many(Session)]` adds a `load_with_sessions` method to the controller, loading the
+///   matching rows paired with their children in two queries, skipping soft-deleted sessions,
+///   and a `create_with_sessions` method inserting an account and its sessions in one
+///   transaction. `Session` must declare an `#[fk(Account)]` field.
+/// - `#[many_to_many(Role, through = "account_roles")]` adds `attach_roles`, `detach_roles`,
+///   `sync_roles` and `load_roles` methods to the controller, going through the `account_id`
+///   and `role_id` columns of the `account_roles` join table. `load_roles` skips soft-deleted
+///   roles.
+/// - `#[rename_all = "camelCase"]` sets the serde `rename_all` policy of the generated structs.
+///
+/// ### Field attributes
+/// - `#[optional]` makes the field nullable.
+/// - `#[fk]` moves the field out of the inner model into the `WithId` struct. With a target,
+///   as in `#[fk(Verification)]`, the `WithId` struct also `belongs_to` `VerificationWithId`
+///   and the controller gains a `verification(&model)` method loading the parent and a
+///   `create_for_verification` method inserting models on behalf of a parent. Only one field
+///   may target a given resource, further keys to it are declared with `#[fk]` alone.
+/// - `#[version]` moves the field into the `WithId` struct and uses it for optimistic locking.
+///   It starts at 1 and is incremented on every update. Updates go through
+///   `ResourceVersioned::update_versioned`, which locks the matching rows and only updates them
+///   when still at the expected version, returning `ResourceError::Conflict` otherwise and
+///   `NotFound` when no row matches. The controller doesn't implement `ResourceUpdate`.
+/// - `#[jsonb]` stores the field, any `Serialize + DeserializeOwned + Debug` type, in a `jsonb`
+///   column. The generated field is wrapped in `Json`, so `#[jsonb] settings: Settings` becomes
+///   `pub settings: Json<Settings>`, set as `Json(settings)` and dereferencing to `Settings`; the
+///   builder setter takes the `Settings` itself. `AccountFilter` gains `<field>_contains` (`@>`),
+///   taking any serializable value, and `<field>_has_key` (`?`) helpers for it.
+/// - `#[sql_type = "varchar(32)"]` sets the column type in `AccountController::CREATE_TABLE`,
+///   which is otherwise inferred from the field type, and reads the column through
+///   `FromSqlValue`, so that newtypes don't need to derive `FromSqlRow`. As the field is still
+///   inserted and updated by diesel, the type needs `FromSql` for reading and, for writing,
+///   `ToSql` along with `#[derive(AsExpression)]` and the diesel SQL type of the column, as in
+///   `#[sql_type = "diesel::sql_types::Varchar"]` on the newtype.
+/// - `#[generated]` and `#[readonly]` move the field into the `WithId` struct, so that columns
+///   filled by a `DEFAULT` or maintained by a trigger are read but never inserted or updated.
+///   `#[generated(default = "gen_random_uuid()")]` gives the `DEFAULT` of the column in
+///   `CREATE_TABLE`. Without one, the column is left for a trigger to fill, and is `NOT NULL`
+///   like any other column unless the field is `#[optional]`.
+/// - `#[sensitive]` marks a write-only field, such as a password. It is inserted and updated like
+///   any other field, but never serialized and redacted in the `Debug` output.
+/// - `#[hashed(argon2)]` replaces a `Password::Plain` in a `Password` field with its hash on
+///   every write, through the `PasswordHasher` named by the scheme (`Argon2`, with the `argon2`
+///   feature), and adds a `verify_<field>(by, candidate)` method to the controller. The model
+///   derives `Clone`, as the hash is written from a copy. Reads return `Password::Hashed`, which
+///   writes keep as is, so that a model read back can be updated without hashing its hash again.
+/// - `#[encrypted]` encrypts a `String` field on every write with the `KeyProvider` registered
+///   through `set_key_provider`, and decrypts it when read, failing the query with a
+///   `DeserializationError` when a value can't be decrypted. Values are prefixed with the id of
+///   their key, so that keys can be rotated while older values remain readable.
+/// - `#[normalize(trim, lowercase)]` applies the steps, in order, to a `String` field on every
+///   write, and adds a `find_by_<field>` method to the controller, normalizing its argument the
+///   same way. `uppercase` is also available.
+/// - `#[slug(from = "name")]` slugifies a `String` field on every write, deriving it from the
+///   `name` field when empty, and adds a `find_by_<field>` method slugifying its argument.
+/// - `#[validate(...)]` adds checks to the `validate` method of the model, which every write
+///   calls after normalization, failing with `ResourceError::Validation` and the errors of each
+///   field. The checks are `length(min = 3, max = 32)`, in characters, `email`, `regex = "..."`,
+///   with the `regex` feature, and `range(min = 0, max = 100)`, where either bound may be omitted.
+///   Regex patterns are compiled when the resource is expanded, failing the build when invalid,
+///   and once more on the first check.
+/// - `#[default = expr]` gives the field a default in the `Default` impl of the model, so that
+///   `Account { username, ..Default::default() }` fills the omitted fields. The impl is only
+///   generated when every other field is an `Option` or a `#[slug]`, which default to `None` and
+///   an empty string, and the builder covers the other models.
+/// - `#[db_default]` makes the field an `Option` in the model, inserting `DEFAULT` for `None` and
+///   leaving the column as is on updates. It is read as `Some`. As with `#[generated]`,
+///   `#[db_default(default = "5")]` gives the `DEFAULT` of the column in `CREATE_TABLE`.
+/// - `#[column = "usrName"]` maps the field to a differently named column of the schema module,
+///   through `#[column_name]` on the generated structs.
+///
+/// Any other attribute, such as `#[serde(skip_serializing)]`, is forwarded as is to the generated
+/// structs or fields. Doc comments on the struct are also forwarded to the controller, and
+/// `#[derive(...)]` adds derives to the generated structs, skipping those already generated. The
+/// generated structs and controller keep the visibility of the struct.
+///
+/// `Vec<T>` fields map to array columns, and `AccountFilter` gains `<field>_contains` (`@>`),
+/// `<field>_overlaps` (`&&`) and `<field>_any` (`= ANY`) helpers for those not `#[optional]`.
+///
+/// Beyond the types diesel maps, `Interval` maps to `interval`, `CiString` to `Citext` columns and
+/// `TstzRange` to `tstzrange`, with `<field>_contains` and `<field>_overlaps` helpers. With the
+/// `network-address` feature `IpNetwork` maps to `inet` and `cidr`, with `<field>_within` (`<<=`)
+/// and `<field>_contains` (`>>=`) helpers, and with the `numeric` feature `BigDecimal` maps to
+/// `numeric`.
+///
+/// The `id` of `AccountWithId` is an `AccountId`, a newtype of `i32` usable as an `Integer`
+/// expression, which the generated methods taking ids expect. `#[fk(Verification)]` fields are
+/// declared with the `VerificationId` of their parent.
+///
+/// `Account::builder()` returns an `AccountBuilder`, with a setter for each field of the model.
+/// Its `build` method fails with `ResourceError::MissingField` when a field which is neither
+/// optional nor defaulted was not set, and its `create` method builds the model and creates it
+/// through the controller.
 ///
 /// ### Model Definition
-/// ```
+/// ```ignore
 /// #[resource]
-/// struct Account {
+/// pub struct Account {
 ///     #[optional]
 ///     uuid: Uuid,
 ///
@@ -138,22 +348,27 @@ impl Input {
 ///     email: String,
 ///
 ///     #[optional]
-///     #[fk]
-///     verification_id: i32,
+///     #[fk(Verification)]
+///     verification_id: VerificationId,
 /// }
 /// ```
 ///
 /// ### Generated result
-/// ```
+/// ```ignore
+/// #[derive(Serialize, Deserialize, FromSqlRow, AsExpression, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// #[sql_type = "diesel::sql_types::Integer"]
+/// #[serde(transparent)]
+/// pub struct AccountId(pub i32);
+///
 /// #[derive(Serialize, Deserialize, FromSqlRow, Associations, Identifiable, Debug, PartialEq)]
+/// #[belongs_to(VerificationWithId, foreign_key = "verification_id")]
 /// #[table_name = "accounts"]
 /// pub struct AccountWithId {
-///     pub id: i32,
+///     pub id: AccountId,
 ///     pub account: Account,
-///     pub verification_id: Option<i32>,
+///     pub verification_id: Option<VerificationId>,
 /// }
 /// #[derive(Serialize, Deserialize, FromSqlRow, Insertable, AsChangeset, Debug, PartialEq)]
-/// #[belongs_to(Verification)]
 /// #[table_name = "accounts"]
 /// pub struct Account {
 ///     pub uuid: Option<Uuid>,
@@ -162,10 +377,10 @@ impl Input {
 ///     pub email: Option<String>,
 /// }
 /// impl diesel::Queryable<accounts::SqlType, diesel::pg::Pg> for AccountWithId {
-///     type Row = (i32, Option<Uuid>, Option<String>, Option<String>, Option<String>, Option<i32>);
+///     type Row = (i32, Option<Uuid>, Option<String>, Option<String>, Option<String>, Option<VerificationId>);
 ///     fn build(row: Self::Row) -> Self {
 ///         AccountWithId {
-///             id: row.0,
+///             id: AccountId(row.0),
 ///             account: Account { uuid: row.1, username: row.2, password: row.3, email: row.4 },
 ///             verification_id: row.5,
 ///         }
@@ -186,31 +401,44 @@ impl Input {
 /// }
 ///
 /// impl ResourceController for AccountController {
-///     fn create(&self, model: &Self::Model) -> Result<Self::ModelWithId, Error> {
+///     fn create(&self, model: &Self::Model) -> Result<Self::ModelWithId, ResourceError> {
 ///         Ok(insert_into(crate::schema::accounts::table)
 ///            .values(model)
 ///            .get_result(&self.connection())?)
 ///     }
 ///
-///     fn get_one(&self, by: Expr<crate::schema::accounts::table>) -> Result<Self::ModelWithId, Error> {
+///     fn get_one(&self, by: Expr<crate::schema::accounts::table>) -> Result<Self::ModelWithId, ResourceError> {
 ///         Ok(crate::schema::accounts::table)
 ///            .filter(by)
 ///            .get_result::<Self::ModelWithId>(&self.connection())?)
 ///     }
 ///
-///     fn get_all(&self, by: Expr<crate::schema::accounts::table>) -> Result<Vec<Self::ModelWithId>, Error> {
+///     fn get_all(&self, by: Expr<crate::schema::accounts::table>) -> Result<Vec<Self::ModelWithId>, ResourceError> {
 ///         Ok(crate::schema::accounts::table)
 ///            .filter(by)
 ///            .get_results::<Self::ModelWithId>(&self.connection())?)
 ///     }
+/// }
 ///
-///     fn update(&self, model: &Self::Model, by: Expr<crate::schema::accounts::table>) -> Result<Self::ModelWithId, Error> {
+/// impl ResourceUpdate for AccountController {
+///     fn update(&self, model: &Self::Model, by: Expr<crate::schema::accounts::table>) -> Result<Self::ModelWithId, ResourceError> {
 ///         Ok(update(crate::schema::accounts::table)
 ///            .filter(by)
 ///            .set(model)
 ///            .get_result::<Self::ModelWithId>(&self.connection())?)
 ///     }
 /// }
+///
+/// impl AccountController {
+///     pub fn verification(&self, model: &AccountWithId) -> Result<Option<VerificationWithId>, ResourceError> {
+///         match model.verification_id {
+///             Some(id) => Ok(Some(crate::schema::verifications::table
+///                .find(id)
+///                .get_result::<VerificationWithId>(&self.connection())?)),
+///             None => Ok(None),
+///         }
+///     }
+/// }
 /// ```
 
 #[proc_macro_attribute]
@@ -218,9 +446,18 @@ pub fn resource(_: TokenStream, input: TokenStream) -> TokenStream {
     let parsed_struct = parse_macro_input!(input as Struct);
     let parsed = Input { parsed_struct };
 
-    let model = parsed.gen_model().unwrap();
-    let controller = parsed.gen_controller().unwrap();
-    let queryable = parsed.gen_queryable().unwrap();
+    let model = match parsed.gen_model() {
+        Ok(model) => model,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let controller = match parsed.gen_controller() {
+        Ok(controller) => controller,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let queryable = match parsed.gen_queryable() {
+        Ok(queryable) => queryable,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     let generated = quote_spanned! {Span::call_site()=>
         #model
@@ -230,3 +467,35 @@ pub fn resource(_: TokenStream, input: TokenStream) -> TokenStream {
 
     generated.into()
 }
+
+///
+/// ### Enum Definition
+/// ```ignore
+/// #[resource_enum]
+/// #[type_name = "account_status"]
+/// enum AccountStatus {
+///     Active,
+///     Suspended,
+/// }
+/// ```
+///
+/// Generates the `AccountStatusType` SQL type for the `account_status` Postgres enum, to be used
+/// in `table!`, along with `ToSql`/`FromSql` impls and serde support for `AccountStatus`. The
+/// variants map to their snake case names, and `AccountStatus::CREATE_TYPE` and
+/// `AccountStatus::DROP_TYPE` hold the statements for migrations. `#[type_name]` defaults to the
+/// snake case enum name and is also `<AccountStatus as ResourceEnum>::TYPE_NAME`.
+///
+/// The visibility and the other attributes of the enum are kept, and its `#[derive]`s are added to
+/// the generated ones. `AccountStatusType` shares the visibility of the enum, which must be `pub`
+/// to appear in `table!`. `CREATE_TABLE` infers the column type of an enum field as its default
+/// type name, so fields of an enum with another `#[type_name]` need
+/// `#[sql_type = "account_status"]`, which is checked at compile time.
+
+#[proc_macro_attribute]
+pub fn resource_enum(_: TokenStream, input: TokenStream) -> TokenStream {
+    let parsed_enum = parse_macro_input!(input as Enum);
+
+    let generated = parsed_enum.gen();
+
+    generated.into()
+}
@@ -0,0 +1,275 @@
+use crate::{builder::*, field::Field, IdentExt};
+
+use syn::{parse::Result, GenericArgument, LitStr, PathArguments, PathSegment, Type};
+
+/// The Postgres type of the Rust types known to the migration.
+fn known_sql_type(ident: &str) -> Option<&'static str> {
+    let sql_type = match ident {
+        "i16" => "SMALLINT",
+        "i32" => "INTEGER",
+        "i64" => "BIGINT",
+        "f32" => "REAL",
+        "f64" => "DOUBLE PRECISION",
+        "bool" => "BOOLEAN",
+        "String" | "Password" => "TEXT",
+        "Uuid" => "UUID",
+        "NaiveDateTime" => "TIMESTAMP",
+        "DateTime" => "TIMESTAMPTZ",
+        "NaiveDate" => "DATE",
+        "NaiveTime" => "TIME",
+        "Interval" => "INTERVAL",
+        "CiString" => "CITEXT",
+        "TstzRange" => "TSTZRANGE",
+        "IpNetwork" => "INET",
+        "BigDecimal" => "NUMERIC",
+        "Value" | "Json" => "JSONB",
+        _ => return None,
+    };
+
+    Some(sql_type)
+}
+
+/// The element type of a `Vec`.
+fn array_element(segment: &PathSegment) -> Option<&Type> {
+    match segment.arguments {
+        PathArguments::AngleBracketed(ref args) => {
+            match args.args.first().map(|arg| arg.into_value()) {
+                Some(GenericArgument::Type(element)) => Some(element),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_u8(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.is_ident("u8"),
+        _ => false,
+    }
+}
+
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|segment| segment.into_value()),
+        _ => None,
+    }
+}
+
+/// Infers the Postgres type of a column from the Rust type of its field. Unknown types are
+/// assumed to be `#[resource_enum]`s with their default type name.
+fn infer_sql_type(ty: &Type) -> String {
+    let segment = match last_segment(ty) {
+        Some(segment) => segment,
+        None => return String::from("TEXT"),
+    };
+
+    if let Some(sql_type) = known_sql_type(&segment.ident.to_string()) {
+        return String::from(sql_type);
+    }
+
+    if segment.ident == "Vec" {
+        return match array_element(segment) {
+            Some(element) if is_u8(element) => String::from("BYTEA"),
+            Some(element) => format!("{}[]", infer_sql_type(element)),
+            None => String::from("TEXT[]"),
+        };
+    }
+
+    segment.ident.snake_case().to_string()
+}
+
+/// The type of a column, or of the elements of an array column, assumed to be a
+/// `#[resource_enum]` by `infer_sql_type`.
+fn inferred_enum(ty: &Type) -> Option<&Type> {
+    let segment = last_segment(ty)?;
+
+    if known_sql_type(&segment.ident.to_string()).is_some() {
+        None
+    } else if segment.ident == "Vec" {
+        array_element(segment).filter(|element| !is_u8(element)).and_then(inferred_enum)
+    } else {
+        Some(ty)
+    }
+}
+
+/// Quotes a column name that Postgres would otherwise fold to lowercase.
+fn quote_column(column: &str) -> String {
+    if column.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+        String::from(column)
+    } else {
+        format!("\"{}\"", column)
+    }
+}
+
+fn column_definition(field: &Field) -> String {
+    let mut definition = format!("{} ", quote_column(&field.column().to_string()));
+
+    match field.sql_type() {
+        Some(sql_type) => definition.push_str(&sql_type.value()),
+        None if field.jsonb() => definition.push_str("JSONB"),
+        None if field.fk() => definition.push_str("INTEGER"),
+        None => definition.push_str(&infer_sql_type(field.declared_ty())),
+    }
+
+    // Columns filled by the database are still read as non-null unless `#[optional]`
+    if !field.optional() {
+        definition.push_str(" NOT NULL");
+    }
+
+    if field.version() {
+        definition.push_str(" DEFAULT 1");
+    } else if let Some(default) = field.column_default() {
+        definition.push_str(&format!(" DEFAULT {}", default.value()));
+    }
+
+    if let Some(target) = field.fk_target() {
+        definition.push_str(&format!(" REFERENCES {}(id)", target.append("s").snake_case()));
+    }
+
+    definition
+}
+
+/// Checks at compile time that the type name inferred for the column of an enum field is the
+/// `#[type_name]` of the enum, as the statements are built before the enum is known.
+fn enum_check(field: &Field) -> Option<proc_macro2::TokenStream> {
+    if field.sql_type().is_some() || field.jsonb() || field.fk() {
+        return None;
+    }
+
+    let ty = inferred_enum(field.declared_ty())?;
+    let type_name = infer_sql_type(ty);
+    let message = LitStr::new(
+        &format!(
+            "the column of `{}` is inferred as `{}`, add #[sql_type = \"...\"] for a #[resource_enum] with another #[type_name]",
+            field.name, type_name
+        ),
+        field.name.span(),
+    );
+
+    Some(quote_spanned! {field.name.span()=>
+        const _: () = assert!(is_type_name(<#ty as ResourceEnum>::TYPE_NAME, #type_name), #message);
+    })
+}
+
+pub struct Migration;
+
+impl<'i> Builder<'i> for Migration {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let controller = input.parsed_struct.controller_name();
+        let table = table_name(input);
+
+        let mut columns = vec![String::from("id SERIAL PRIMARY KEY")];
+        columns.extend(input.parsed_struct.fields.iter().map(column_definition));
+
+        if input.parsed_struct.attrs.timestamps {
+            columns.push(String::from("created_at TIMESTAMP NOT NULL DEFAULT now()"));
+            columns.push(String::from("updated_at TIMESTAMP NOT NULL DEFAULT now()"));
+        }
+
+        if input.parsed_struct.attrs.soft_delete {
+            columns.push(String::from("deleted_at TIMESTAMP"));
+        }
+
+        let create_table = format!("CREATE TABLE {} (\n    {}\n)", table, columns.join(",\n    "));
+        let drop_table = format!("DROP TABLE {}", table);
+        let enum_checks = input.parsed_struct.fields.iter().filter_map(enum_check);
+
+        Ok(quote! {
+            #(#enum_checks)*
+
+            impl #controller {
+                pub const CREATE_TABLE: &'static str = #create_table;
+                pub const DROP_TABLE: &'static str = #drop_table;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sql_type(ty: &str) -> String {
+        infer_sql_type(&syn::parse_str(ty).unwrap())
+    }
+
+    fn column(field: &str) -> String {
+        column_definition(&syn::parse_str(field).unwrap())
+    }
+
+    #[test]
+    fn sql_types_are_inferred() {
+        assert_eq!(sql_type("i32"), "INTEGER");
+        assert_eq!(sql_type("String"), "TEXT");
+        assert_eq!(sql_type("chrono::DateTime<Utc>"), "TIMESTAMPTZ");
+        assert_eq!(sql_type("serde_json::Value"), "JSONB");
+        assert_eq!(sql_type("Vec<u8>"), "BYTEA");
+        assert_eq!(sql_type("Vec<i64>"), "BIGINT[]");
+        assert_eq!(sql_type("Vec<Vec<String>>"), "TEXT[][]");
+        assert_eq!(sql_type("(i32, i32)"), "TEXT");
+
+        // Unknown types are resource enums
+        assert_eq!(sql_type("AccountStatus"), "account_status");
+        assert_eq!(sql_type("Vec<AccountStatus>"), "account_status[]");
+    }
+
+    #[test]
+    fn enum_columns_are_checked() {
+        let inferred = |ty: &str| {
+            let ty: Type = syn::parse_str(ty).unwrap();
+            inferred_enum(&ty).map(|ty| quote!(#ty).to_string())
+        };
+
+        assert_eq!(inferred("AccountStatus"), Some(String::from("AccountStatus")));
+        assert_eq!(inferred("Vec<AccountStatus>"), Some(String::from("AccountStatus")));
+        assert_eq!(inferred("Vec<u8>"), None);
+        assert_eq!(inferred("Vec<String>"), None);
+        assert_eq!(inferred("Uuid"), None);
+
+        assert!(enum_check(&syn::parse_str("status: AccountStatus").unwrap()).is_some());
+        assert!(enum_check(
+            &syn::parse_str("#[sql_type = \"status\"] status: AccountStatus").unwrap()
+        )
+        .is_none());
+        assert!(enum_check(&syn::parse_str("#[jsonb] status: AccountStatus").unwrap()).is_none());
+    }
+
+    #[test]
+    fn columns_are_quoted_when_not_lowercase() {
+        assert_eq!(quote_column("username"), "username");
+        assert_eq!(quote_column("account_id2"), "account_id2");
+        assert_eq!(quote_column("accountRef"), "\"accountRef\"");
+        assert_eq!(quote_column("user name"), "\"user name\"");
+    }
+
+    #[test]
+    fn columns_are_defined() {
+        assert_eq!(column("name: String"), "name TEXT NOT NULL");
+        assert_eq!(column("#[optional] name: String"), "name TEXT");
+        assert_eq!(column("#[column = \"usrName\"] name: String"), "\"usrName\" TEXT NOT NULL");
+        assert_eq!(column("#[sql_type = \"varchar(32)\"] code: Code"), "code varchar(32) NOT NULL");
+        assert_eq!(column("#[jsonb] settings: Settings"), "settings JSONB NOT NULL");
+        assert_eq!(column("#[version] version: i32"), "version INTEGER NOT NULL DEFAULT 1");
+        assert_eq!(
+            column("#[fk(Account)] account_id: AccountId"),
+            "account_id INTEGER NOT NULL REFERENCES accounts(id)"
+        );
+    }
+
+    #[test]
+    fn database_filled_columns_are_not_null() {
+        assert_eq!(
+            column("#[generated(default = \"gen_random_uuid()\")] uuid: Uuid"),
+            "uuid UUID NOT NULL DEFAULT gen_random_uuid()"
+        );
+        assert_eq!(column("#[generated] uuid: Uuid"), "uuid UUID NOT NULL");
+        assert_eq!(column("#[readonly] search: String"), "search TEXT NOT NULL");
+        assert_eq!(column("#[optional] #[readonly] search: String"), "search TEXT");
+        assert_eq!(
+            column("#[db_default(default = \"5\")] weight: i32"),
+            "weight INTEGER NOT NULL DEFAULT 5"
+        );
+        assert_eq!(column("#[db_default] weight: i32"), "weight INTEGER NOT NULL");
+    }
+}
@@ -1,16 +1,62 @@
-use crate::builder::{Input, Builder};
+use crate::builder::{Builder, Input};
 
-use syn::parse::Result; 
+use proc_macro2::Span;
+use syn::{parse::Result, Ident, Path};
+
+/// The derives of a generated struct, merged with the derives declared on the resource struct.
+/// `Debug` is only derived when generated, as structs holding `#[sensitive]` fields implement it,
+/// and `Default` is skipped when the model implements it for its field defaults.
+pub struct Derives(pub Vec<&'static str>);
+
+impl<'i> Builder<'i> for Derives {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let mut derives = self
+            .0
+            .iter()
+            .map(|derive| Ident::new(derive, Span::call_site()).into())
+            .collect::<Vec<Path>>();
+
+        input.parsed_struct.attrs.derives.iter().for_each(|derive| {
+            let name = derive.segments.last().map(|pair| pair.value().ident.to_string());
+            let name = name.as_deref();
+            let implemented = name == Some("Debug") || (name == Some("Default") && defaults(input));
+            if !implemented && !self.0.iter().any(|generated| name == Some(*generated)) {
+                derives.push(derive.clone());
+            }
+        });
+
+        Ok(quote!(#[derive(#(#derives),*)]))
+    }
+}
+
+/// The attributes forwarded from the resource struct, shared by the `Model` and `WithId` structs.
+struct ModelAttrs;
+
+impl<'i> Builder<'i> for ModelAttrs {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let docs = &input.parsed_struct.attrs.docs;
+        let forward = &input.parsed_struct.attrs.forward;
+
+        if let Some(ref rename_all) = input.parsed_struct.attrs.rename_all {
+            Ok(quote!(#(#docs)* #(#forward)* #[serde(rename_all = #rename_all)]))
+        } else {
+            Ok(quote!(#(#docs)* #(#forward)*))
+        }
+    }
+}
 
 pub struct ModelWithId;
 
 impl<'i> Builder<'i> for ModelWithId {
     fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let vis = &input.parsed_struct.vis;
         let model_name_with_id = input.parsed_struct.model_name_with_id();
+        let attrs = ModelAttrs.build(input)?;
         let fields = ModelWithIdFields.build(input)?;
 
         Ok(quote! {
-            pub struct #model_name_with_id {
+            #attrs
+            #vis struct #model_name_with_id {
                 #fields
             }
         })
@@ -22,19 +68,30 @@ struct ModelWithIdFields;
 impl<'i> Builder<'i> for ModelWithIdFields {
     fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
         let mut fields = Vec::new();
-        fields.push(quote!(pub id: i32));
+        let id = input.parsed_struct.id_name();
+        fields.push(quote!(pub id: #id));
 
         let model_name = &input.parsed_struct.ident;
         fields.push(quote!(pub inner: #model_name));
 
         input.parsed_struct.fields.iter().for_each(|field| {
-            if field.fk() {
+            if !field.inner() {
                 let ty = field.ty();
                 let name = &field.name;
-                fields.push(quote!(pub #name: #ty));
+                let attrs = field.attrs();
+                fields.push(quote!(#attrs pub #name: #ty));
             }
         });
 
+        if input.parsed_struct.attrs.timestamps {
+            fields.push(quote!(pub created_at: chrono::NaiveDateTime));
+            fields.push(quote!(pub updated_at: chrono::NaiveDateTime));
+        }
+
+        if input.parsed_struct.attrs.soft_delete {
+            fields.push(quote!(pub deleted_at: Option<chrono::NaiveDateTime>));
+        }
+
         Ok(quote!(#(#fields,)*))
     }
 }
@@ -43,11 +100,14 @@ pub struct Model;
 
 impl<'i> Builder<'i> for Model {
     fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let vis = &input.parsed_struct.vis;
         let model_name = &input.parsed_struct.ident;
+        let attrs = ModelAttrs.build(input)?;
         let fields = ModelFields.build(input)?;
 
         Ok(quote! {
-            pub struct #model_name {
+            #attrs
+            #vis struct #model_name {
                 #fields
             }
         })
@@ -60,10 +120,11 @@ impl<'i> Builder<'i> for ModelFields {
     fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
         let mut fields = Vec::new();
         input.parsed_struct.fields.iter().for_each(|field| {
-            if !field.fk() {
+            if field.inner() {
                 let ty = field.ty();
                 let name = &field.name;
-                fields.push(quote!(pub #name: #ty));
+                let attrs = field.attrs();
+                fields.push(quote!(#attrs pub #name: #ty));
             }
         });
 
@@ -71,3 +132,45 @@ impl<'i> Builder<'i> for ModelFields {
     }
 }
 
+/// Whether the model implements `Default` for its `#[default]` and `#[db_default]` fields, which
+/// requires every other field to be an `Option` or a slug, as their types may not be `Default`.
+pub fn defaults(input: &Input) -> bool {
+    let mut fields = input.parsed_struct.fields.iter().filter(|field| field.inner());
+
+    fields.clone().any(|field| field.default().is_some() || field.db_default())
+        && fields.all(|field| {
+            field.default().is_some() || field.is_option() || field.slug_from().is_some()
+        })
+}
+
+pub struct ModelDefault;
+
+impl<'i> Builder<'i> for ModelDefault {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if !defaults(input) {
+            return Ok(quote!());
+        }
+
+        let model_name = &input.parsed_struct.ident;
+
+        let fields = input.parsed_struct.fields.iter().filter(|field| field.inner()).map(|field| {
+            let name = &field.name;
+            match (field.default(), field.is_option()) {
+                (Some(expr), false) => quote!(#name: #expr),
+                (Some(expr), true) => quote!(#name: Some(#expr)),
+                (None, true) => quote!(#name: None),
+                (None, false) => quote!(#name: Default::default()),
+            }
+        });
+
+        Ok(quote! {
+            impl Default for #model_name {
+                fn default() -> Self {
+                    #model_name {
+                        #(#fields,)*
+                    }
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use crate::builder::*;
+
+use syn::{parse::Result, LitStr};
+
+/// A builder for the model, with a setter for each field, checking required fields on `build`.
+pub struct ModelBuilder;
+
+impl<'i> Builder<'i> for ModelBuilder {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let vis = &input.parsed_struct.vis;
+        let model = input.parsed_struct.inner_model_name();
+        let builder = input.parsed_struct.builder_name();
+        let model_with_id = input.parsed_struct.model_name_with_id();
+        let controller = input.parsed_struct.controller_name();
+
+        let fields =
+            input.parsed_struct.fields.iter().filter(|field| field.inner()).collect::<Vec<_>>();
+
+        let builder_fields = fields.iter().map(|field| {
+            let name = &field.name;
+            let ty = field.value_ty();
+            quote!(#name: Option<#ty>)
+        });
+
+        let setters = fields.iter().map(|field| {
+            let name = &field.name;
+            if field.jsonb() {
+                let ty = field.declared_ty();
+                quote! {
+                    pub fn #name(mut self, value: #ty) -> Self {
+                        self.#name = Some(Json(value));
+                        self
+                    }
+                }
+            } else {
+                let ty = field.value_ty();
+                quote! {
+                    pub fn #name<T: Into<#ty>>(mut self, value: T) -> Self {
+                        self.#name = Some(value.into());
+                        self
+                    }
+                }
+            }
+        });
+
+        let values = fields.iter().map(|field| {
+            let name = &field.name;
+            let literal = LitStr::new(&name.to_string(), name.span());
+
+            match (field.default(), field.is_option()) {
+                (Some(expr), true) => quote!(#name: self.#name.or_else(|| Some(#expr))),
+                (Some(expr), false) => quote!(#name: self.#name.unwrap_or_else(|| #expr)),
+                (None, true) => quote!(#name: self.#name),
+                (None, false) if field.slug_from().is_some() => {
+                    quote!(#name: self.#name.unwrap_or_default())
+                }
+                (None, false) => {
+                    quote!(#name: self.#name.ok_or(ResourceError::MissingField(#literal))?)
+                }
+            }
+        });
+
+        Ok(quote! {
+            #[derive(Default)]
+            #vis struct #builder {
+                #(#builder_fields,)*
+            }
+
+            impl #builder {
+                #(#setters)*
+
+                pub fn build(self) -> Result<#model, ResourceError> {
+                    Ok(#model {
+                        #(#values,)*
+                    })
+                }
+
+                pub fn create(self) -> Result<#model_with_id, ResourceError> {
+                    #controller.create(&self.build()?)
+                }
+            }
+
+            impl #model {
+                pub fn builder() -> #builder {
+                    #builder::default()
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{builder::*, field::Field, IdentExt};
+
+use syn::{parse::Result, Error};
+
+/// The `normalize` call applying the `#[normalize(...)]` steps of `field` to `value`, a `&str`.
+fn normalize_call(field: &Field, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let steps = field.normalize();
+    quote!(normalize(#value, &[#(Normalize::#steps),*]))
+}
+
+/// Normalizes the fields of `model`, then fills empty `#[slug]` fields from their source.
+pub struct NormalizeSteps;
+
+impl<'i> Builder<'i> for NormalizeSteps {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let fields = &input.parsed_struct.fields;
+        let mut steps = Vec::new();
+
+        fields.iter().filter(|field| !field.normalize().is_empty()).for_each(|field| {
+            let name = &field.name;
+            let normalized = normalize_call(field, quote!(value));
+
+            if field.is_option() {
+                steps.push(quote!(model.#name = model.#name.as_ref().map(|value| #normalized);));
+            } else {
+                steps.push(quote!(model.#name = { let value = &model.#name; #normalized };));
+            }
+        });
+
+        for field in fields.iter() {
+            let from = match field.slug_from() {
+                Some(from) => from,
+                None => continue,
+            };
+
+            let source = fields.iter().find(|source| source.name == *from).ok_or_else(|| {
+                Error::new(from.span(), format!("no field named {} to derive the slug from", from))
+            })?;
+
+            let name = &field.name;
+            let slug = if source.is_option() {
+                quote!(model.#from.as_ref().map(|value| slugify(value)).unwrap_or_default())
+            } else {
+                quote!(slugify(&model.#from))
+            };
+
+            if field.is_option() {
+                steps.push(quote! {
+                    model.#name = match model.#name.take() {
+                        Some(ref value) if !value.is_empty() => Some(slugify(value)),
+                        _ => Some(#slug),
+                    };
+                });
+            } else {
+                steps.push(quote! {
+                    model.#name = if model.#name.is_empty() { #slug } else { slugify(&model.#name) };
+                });
+            }
+        }
+
+        Ok(quote!(#(#steps)*))
+    }
+}
+
+/// `find_by_<field>` methods for normalized and slug fields, which normalize their argument the
+/// same way the field is normalized on writes.
+pub struct NormalizedFinders;
+
+impl<'i> Builder<'i> for NormalizedFinders {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+        let controller = input.parsed_struct.controller_name();
+
+        let mut finders = Vec::new();
+
+        input.parsed_struct.fields.iter().for_each(|field| {
+            let lookup = if field.slug_from().is_some() {
+                quote!(slugify(value))
+            } else if !field.normalize().is_empty() {
+                normalize_call(field, quote!(value))
+            } else {
+                return;
+            };
+
+            // Encrypted values can't be looked up
+            if field.encrypted() {
+                return;
+            }
+
+            let column = field.column();
+            let method = field.name.prepend("find_by_");
+
+            finders.push(quote! {
+                pub fn #method(&self, value: &str) -> Result<<Self as ResourceWithId>::ModelWithId, ResourceError> {
+                    self.get_one(Box::new(#schema::#column.eq(#lookup)))
+                }
+            });
+        });
+
+        if finders.is_empty() {
+            return Ok(quote!());
+        }
+
+        Ok(quote! {
+            impl #controller {
+                #(#finders)*
+            }
+        })
+    }
+}
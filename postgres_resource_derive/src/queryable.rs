@@ -10,10 +10,19 @@ impl<'i> Builder<'i> for QueryableRow {
         fields.push(quote!(i32));
 
         input.parsed_struct.fields.iter().for_each(|field| {
-            let ty = field.ty();
+            let ty = field.row_ty();
             fields.push(quote!(#ty));
         });
 
+        if input.parsed_struct.attrs.timestamps {
+            fields.push(quote!(chrono::NaiveDateTime));
+            fields.push(quote!(chrono::NaiveDateTime));
+        }
+
+        if input.parsed_struct.attrs.soft_delete {
+            fields.push(quote!(Option<chrono::NaiveDateTime>));
+        }
+
         Ok(quote!(type Row = (#(#fields,)*);))
     }
 }
@@ -27,18 +36,17 @@ impl<'i> Builder<'i> for QueryableFields {
 
         let model_name = input.parsed_struct.inner_model_name();
 
-        let mut index = 0;
-
         // Push id
-        let idx = Index::from(index);
-        fields.push(quote!(id: row.#idx));
+        let id = input.parsed_struct.id_name();
+        fields.push(quote!(id: #id(row.0)));
 
+        // Row columns follow the declaration order of the fields
         input.parsed_struct.fields.iter().enumerate().for_each(|(i, field)| {
             let field_name = &field.name;
-            if !field.fk() {
-                index = i + 1;
-                let idx = Index::from(index);
-                inner_fields.push(quote!(#field_name: row.#idx));
+            if field.inner() {
+                let idx = Index::from(i + 1);
+                let value = field.from_row(quote!(row.#idx));
+                inner_fields.push(quote!(#field_name: #value));
             }
         });
 
@@ -48,15 +56,32 @@ impl<'i> Builder<'i> for QueryableFields {
         fields.push(quote!(inner: #model_name #generated_inner_fields));
 
         // Push remaining fields
-        input.parsed_struct.fields.iter().for_each(|field| {
-            if field.fk() {
-                index = index + 1;
+        input.parsed_struct.fields.iter().enumerate().for_each(|(i, field)| {
+            if !field.inner() {
                 let name = &field.name;
-                let idx = Index::from(index);
-                fields.push(quote!(#name: row.#idx));
+                let idx = Index::from(i + 1);
+                let value = field.from_row(quote!(row.#idx));
+                fields.push(quote!(#name: #value));
             }
         });
 
+        let mut index = input.parsed_struct.fields.len();
+
+        // Push timestamps
+        if input.parsed_struct.attrs.timestamps {
+            let created_at = Index::from(index + 1);
+            let updated_at = Index::from(index + 2);
+            fields.push(quote!(created_at: row.#created_at));
+            fields.push(quote!(updated_at: row.#updated_at));
+            index += 2;
+        }
+
+        // Push soft delete marker
+        if input.parsed_struct.attrs.soft_delete {
+            let deleted_at = Index::from(index + 1);
+            fields.push(quote!(deleted_at: row.#deleted_at));
+        }
+
         Ok(quote!(#(#fields,)*))
     }
 }
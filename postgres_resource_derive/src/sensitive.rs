@@ -0,0 +1,80 @@
+use crate::builder::*;
+
+use syn::{parse::Result, LitStr};
+
+/// Debug entry of a field, with the value of `#[sensitive]` fields redacted.
+fn debug_field(name: &syn::Ident, sensitive: bool) -> proc_macro2::TokenStream {
+    let literal = LitStr::new(&name.to_string(), name.span());
+
+    if sensitive {
+        quote!(.field(#literal, &"[redacted]"))
+    } else {
+        quote!(.field(#literal, &self.#name))
+    }
+}
+
+/// `Debug` impls of the generated structs holding `#[sensitive]` fields, which don't derive it.
+pub struct SensitiveDebug;
+
+impl<'i> Builder<'i> for SensitiveDebug {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let mut impls = Vec::new();
+
+        if input.parsed_struct.sensitive(true) {
+            let model = input.parsed_struct.inner_model_name();
+            let literal = LitStr::new(&model.to_string(), model.span());
+
+            let fields = input
+                .parsed_struct
+                .fields
+                .iter()
+                .filter(|field| field.inner())
+                .map(|field| debug_field(&field.name, field.sensitive()));
+
+            impls.push(quote! {
+                impl std::fmt::Debug for #model {
+                    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        f.debug_struct(#literal)
+                            #(#fields)*
+                            .finish()
+                    }
+                }
+            });
+        }
+
+        if input.parsed_struct.sensitive(false) {
+            let model_with_id = input.parsed_struct.model_name_with_id();
+            let literal = LitStr::new(&model_with_id.to_string(), model_with_id.span());
+
+            let mut fields =
+                vec![quote!(.field("id", &self.id)), quote!(.field("inner", &self.inner))];
+
+            input.parsed_struct.fields.iter().for_each(|field| {
+                if !field.inner() {
+                    fields.push(debug_field(&field.name, field.sensitive()));
+                }
+            });
+
+            if input.parsed_struct.attrs.timestamps {
+                fields.push(quote!(.field("created_at", &self.created_at)));
+                fields.push(quote!(.field("updated_at", &self.updated_at)));
+            }
+
+            if input.parsed_struct.attrs.soft_delete {
+                fields.push(quote!(.field("deleted_at", &self.deleted_at)));
+            }
+
+            impls.push(quote! {
+                impl std::fmt::Debug for #model_with_id {
+                    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        f.debug_struct(#literal)
+                            #(#fields)*
+                            .finish()
+                    }
+                }
+            });
+        }
+
+        Ok(quote!(#(#impls)*))
+    }
+}
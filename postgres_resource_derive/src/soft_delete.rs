@@ -0,0 +1,111 @@
+use crate::{builder::*, hooks::*};
+
+use syn::parse::Result;
+
+/// The changeset setting `deleted_at` to `value`, bumping `updated_at` along with it under
+/// `#[timestamps]`.
+fn deleted_at(input: &Input, value: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+    let schema = Schema.build(input)?;
+
+    if input.parsed_struct.attrs.timestamps {
+        Ok(quote!((#schema::deleted_at.eq(#value), #schema::updated_at.eq(diesel::dsl::now))))
+    } else {
+        Ok(quote!(#schema::deleted_at.eq(#value)))
+    }
+}
+
+/// Deletes the rows matching `by` on `connection`.
+pub struct DeleteQuery;
+
+impl<'i> Builder<'i> for DeleteQuery {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let schema = Schema.build(input)?;
+
+        if input.parsed_struct.attrs.soft_delete {
+            let changes = deleted_at(input, quote!(diesel::dsl::now.nullable()))?;
+            Ok(quote! {
+                update(#schema::table)
+                    .filter(by)
+                    .filter(#schema::deleted_at.is_null())
+                    .set(#changes)
+                    .execute(connection)
+            })
+        } else {
+            Ok(quote!(delete(#schema::table).filter(by).execute(connection)))
+        }
+    }
+}
+
+pub struct SoftDeleteController;
+
+impl<'i> Builder<'i> for SoftDeleteController {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if !input.parsed_struct.attrs.soft_delete {
+            return Ok(quote!());
+        }
+
+        let schema = Schema.build(input)?;
+        let connection = DatabaseConnection.build(input)?;
+        let controller = input.parsed_struct.controller_name();
+
+        let changes = deleted_at(input, quote!(None::<chrono::NaiveDateTime>))?;
+        let restore = quote! {
+            update(#schema::table)
+                .filter(by)
+                .filter(#schema::deleted_at.is_not_null())
+                .set(#changes)
+        };
+        let purge = quote! {
+            delete(#schema::table)
+                .filter(by)
+                .filter(#schema::deleted_at.is_not_null())
+        };
+
+        let (restore, purge) = if hooks(input) {
+            let deleted = quote!(.filter(#schema::deleted_at.is_not_null()));
+            let restore_rows = hooked_rows(input, deleted.clone(), "before_restore")?;
+            let purge_rows = hooked_rows(input, deleted, "before_delete")?;
+
+            let restore = quote! {
+                let connection = #connection;
+                connection.transaction::<_, ResourceError, _>(|| {
+                    #restore_rows
+                    let restored = #restore.get_results::<Self::ModelWithId>(connection)?;
+                    self.after_restore(connection, &restored)?;
+                    Ok(restored.len())
+                })
+            };
+            let purge = quote! {
+                let connection = #connection;
+                connection.transaction::<_, ResourceError, _>(|| {
+                    #purge_rows
+                    let purged = #purge.execute(connection)?;
+                    self.after_delete(connection, &models)?;
+                    Ok(purged)
+                })
+            };
+
+            (restore, purge)
+        } else {
+            (quote!(Ok(#restore.execute(#connection)?)), quote!(Ok(#purge.execute(#connection)?)))
+        };
+
+        Ok(quote! {
+            impl ResourceSoftDelete for #controller {
+                fn with_deleted(&self, by: Expr<#schema::table>) -> Result<Vec<Self::ModelWithId>, ResourceError> {
+                    Ok(#schema::table
+                       .filter(by)
+                       .get_results::<Self::ModelWithId>(#connection)?)
+                }
+
+                fn restore(&self, by: Expr<#schema::table>) -> Result<usize, ResourceError> {
+                    #restore
+                }
+
+                fn purge(&self, by: Expr<#schema::table>) -> Result<usize, ResourceError> {
+                    #purge
+                }
+            }
+        })
+    }
+}
@@ -1,7 +1,7 @@
 use syn::{
     parse::{Parse, ParseStream, Result},
     punctuated::Punctuated,
-    Ident,
+    Error, Ident, Visibility,
 };
 
 use crate::{attr::*, field::*, IdentExt};
@@ -9,6 +9,7 @@ use crate::{attr::*, field::*, IdentExt};
 #[derive(Debug)]
 pub struct Struct {
     pub attrs: Attrs,
+    pub vis: Visibility,
     pub ident: syn::Ident,
     pub fields: Punctuated<Field, Token![,]>,
 }
@@ -16,16 +17,37 @@ pub struct Struct {
 impl Parse for Struct {
     fn parse(input: ParseStream) -> Result<Self> {
         let attrs = input.call(Attrs::parse)?;
+        let vis = input.parse()?;
         let content;
         let _: Token![struct] = input.parse()?;
         let ident = input.parse()?;
         let _ = braced!(content in input);
         let fields = content.parse_terminated(Field::parse)?;
-        Ok(Struct { attrs, ident, fields })
+        Self::check_fk_targets(&fields)?;
+        Ok(Struct { attrs, vis, ident, fields })
     }
 }
 
 impl Struct {
+    /// Rejects a second `#[fk(Resource)]` to the same resource, as the `WithId` struct can only
+    /// belong to it once and its loaders are named after it.
+    fn check_fk_targets(fields: &Punctuated<Field, Token![,]>) -> Result<()> {
+        let mut targets = Vec::new();
+
+        for target in fields.iter().filter_map(Field::fk_target) {
+            if targets.contains(&target) {
+                let message = format!(
+                    "another field is already #[fk({})], use #[fk] without a target for this one",
+                    target
+                );
+                return Err(Error::new(target.span(), message));
+            }
+            targets.push(target);
+        }
+
+        Ok(())
+    }
+
     pub fn model_name_with_id(&self) -> Ident {
         self.ident.append("WithId")
     }
@@ -37,4 +59,26 @@ impl Struct {
     pub fn controller_name(&self) -> Ident {
         self.ident.append("Controller")
     }
+
+    pub fn id_name(&self) -> Ident {
+        self.ident.append("Id")
+    }
+
+    pub fn builder_name(&self) -> Ident {
+        self.ident.append("Builder")
+    }
+
+    pub fn filter_name(&self) -> Ident {
+        self.ident.append("Filter")
+    }
+
+    /// Whether the inner model, or the `WithId` struct when `inner` is false, holds
+    /// `#[sensitive]` fields.
+    pub fn sensitive(&self, inner: bool) -> bool {
+        self.fields.iter().any(|field| field.sensitive() && field.inner() == inner)
+    }
+
+    pub fn version_field(&self) -> Option<&Field> {
+        self.fields.iter().find(|field| field.version())
+    }
 }
@@ -0,0 +1,195 @@
+use crate::builder::*;
+
+use syn::{
+    parse::Result, Attribute, Error, Ident, Lit, LitStr, Meta::*, MetaList, MetaNameValue,
+    NestedMeta,
+};
+
+/// A check of `#[validate(...)]`.
+#[derive(Debug, PartialEq)]
+pub enum Validation {
+    Length { min: Option<Lit>, max: Option<Lit> },
+    Email,
+    Regex(LitStr),
+    Range { min: Option<Lit>, max: Option<Lit> },
+}
+
+impl Validation {
+    pub fn parse_all(attr: &Attribute) -> Result<Vec<Validation>> {
+        let error = || {
+            Error::new(
+                attr.bracket_token.span,
+                "expected #[validate(length(..), email, regex = \"...\", range(..))]",
+            )
+        };
+
+        let list = match attr.parse_meta()? {
+            List(list) => list,
+            _ => return Err(error()),
+        };
+
+        list.nested
+            .iter()
+            .map(|nested| match nested {
+                NestedMeta::Meta(Word(ident)) if ident == "email" => Ok(Validation::Email),
+                NestedMeta::Meta(NameValue(MetaNameValue {
+                    ident,
+                    lit: Lit::Str(pattern),
+                    ..
+                })) if ident == "regex" => {
+                    Self::check_regex(pattern)?;
+                    Ok(Validation::Regex(pattern.clone()))
+                }
+                NestedMeta::Meta(List(bounds)) if bounds.ident == "length" => {
+                    let (min, max) = Self::parse_bounds(bounds).ok_or_else(error)?;
+                    Ok(Validation::Length { min, max })
+                }
+                NestedMeta::Meta(List(bounds)) if bounds.ident == "range" => {
+                    let (min, max) = Self::parse_bounds(bounds).ok_or_else(error)?;
+                    Ok(Validation::Range { min, max })
+                }
+                _ => Err(error()),
+            })
+            .collect()
+    }
+
+    /// Compiles the pattern of `#[validate(regex = "...")]`, so that invalid patterns fail the build.
+    #[cfg(feature = "regex")]
+    fn check_regex(pattern: &LitStr) -> Result<()> {
+        match regex::Regex::new(&pattern.value()) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(Error::new(pattern.span(), error)),
+        }
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn check_regex(pattern: &LitStr) -> Result<()> {
+        Err(Error::new(
+            pattern.span(),
+            "#[validate(regex = \"...\")] requires the `regex` feature of postgres-resource",
+        ))
+    }
+
+    /// The `min` and `max` of `length(min = 3, max = 32)`, either of which may be omitted.
+    fn parse_bounds(list: &MetaList) -> Option<(Option<Lit>, Option<Lit>)> {
+        let mut min = None;
+        let mut max = None;
+
+        for nested in &list.nested {
+            match nested {
+                NestedMeta::Meta(NameValue(MetaNameValue { ident, lit, .. })) if ident == "min" => {
+                    min = Some(lit.clone())
+                }
+                NestedMeta::Meta(NameValue(MetaNameValue { ident, lit, .. })) if ident == "max" => {
+                    max = Some(lit.clone())
+                }
+                _ => return None,
+            }
+        }
+
+        Some((min, max))
+    }
+
+    /// Checks `value`, a reference to the field, adding an error for `field` to `errors`.
+    fn check(&self, field: &Ident) -> proc_macro2::TokenStream {
+        let field = LitStr::new(&field.to_string(), field.span());
+
+        match self {
+            Validation::Length { min, max } => {
+                let min_check = bound_check(min, |min| quote!(length < #min));
+                let max_check = bound_check(max, |max| quote!(length > #max));
+                let min = min.as_ref().map_or(quote!(None), |min| quote!(Some(#min)));
+                let max = max.as_ref().map_or(quote!(None), |max| quote!(Some(#max)));
+                quote! {
+                    let length = ValidateLength::validate_length(value);
+                    if #min_check || #max_check {
+                        errors.add(#field, ValidationError::Length { min: #min, max: #max });
+                    }
+                }
+            }
+            Validation::Email => quote! {
+                if !validate_email(value) {
+                    errors.add(#field, ValidationError::Email);
+                }
+            },
+            Validation::Regex(pattern) => quote! {
+                {
+                    static REGEX: ValidationRegex = ValidationRegex::new(#pattern);
+                    if !REGEX.is_match(value) {
+                        errors.add(#field, ValidationError::Regex { pattern: #pattern });
+                    }
+                }
+            },
+            Validation::Range { min, max } => {
+                let min_check = bound_check(min, |min| quote!(*value < #min));
+                let max_check = bound_check(max, |max| quote!(*value > #max));
+                let min = min.as_ref().map_or(quote!(None), |min| quote!(Some(#min as f64)));
+                let max = max.as_ref().map_or(quote!(None), |max| quote!(Some(#max as f64)));
+                quote! {
+                    if #min_check || #max_check {
+                        errors.add(#field, ValidationError::Range { min: #min, max: #max });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether the value is out of an optional bound.
+fn bound_check<F>(lit: &Option<Lit>, check: F) -> proc_macro2::TokenStream
+where
+    F: Fn(&Lit) -> proc_macro2::TokenStream,
+{
+    lit.as_ref().map_or(quote!(false), check)
+}
+
+/// Whether the model has a `validate` method, called before every write.
+pub fn validates(input: &Input) -> bool {
+    input.parsed_struct.fields.iter().any(|field| field.inner() && !field.validations().is_empty())
+}
+
+pub struct Validations;
+
+impl<'i> Builder<'i> for Validations {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        if !validates(input) {
+            return Ok(quote!());
+        }
+
+        let model = input.parsed_struct.inner_model_name();
+
+        let checks = input.parsed_struct.fields.iter().filter_map(|field| {
+            let validations = field.validations();
+            if !field.inner() || validations.is_empty() {
+                return None;
+            }
+
+            let name = &field.name;
+            let checks = validations.iter().map(|validation| validation.check(name));
+
+            if field.is_option() {
+                Some(quote! {
+                    if let Some(ref value) = self.#name {
+                        #(#checks)*
+                    }
+                })
+            } else {
+                Some(quote! {
+                    let value = &self.#name;
+                    #(#checks)*
+                })
+            }
+        });
+
+        Ok(quote! {
+            impl #model {
+                #[allow(clippy::manual_range_contains)]
+                pub fn validate(&self) -> Result<(), ValidationErrors> {
+                    let mut errors = ValidationErrors::new();
+                    #(#checks)*
+                    errors.into_result()
+                }
+            }
+        })
+    }
+}
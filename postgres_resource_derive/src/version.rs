@@ -0,0 +1,74 @@
+use crate::{builder::*, hooks::*};
+
+use syn::parse::Result;
+
+pub struct VersionedController;
+
+impl<'i> Builder<'i> for VersionedController {
+    fn build(self, input: &'i Input) -> Result<proc_macro2::TokenStream> {
+        let field = match input.parsed_struct.version_field() {
+            Some(field) => field,
+            None => return Ok(quote!()),
+        };
+
+        let schema = Schema.build(input)?;
+        let connection = DatabaseConnection.build(input)?;
+        let changeset = Changeset.build(input)?;
+        let scope = Scope.build(input)?;
+        let controller = input.parsed_struct.controller_name();
+        let id = input.parsed_struct.id_name();
+
+        let column = field.column();
+        let ty = field.ty();
+
+        // The matching rows are locked and checked first, so that a missing row is told apart
+        // from a stale version
+        let write = quote! {{
+            let current = #schema::table
+                .filter(by)
+                #scope
+                .select((#schema::id, #schema::#column))
+                .for_update()
+                .get_results::<(#id, #ty)>(connection)?;
+
+            if current.is_empty() {
+                Err(ResourceError::from(diesel::result::Error::NotFound))
+            } else if current.iter().any(|(_, current)| *current != version) {
+                Err(ResourceError::Conflict)
+            } else {
+                let ids = current.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+                update(#schema::table)
+                    .filter(#schema::id.eq_any(ids))
+                    .set(#changeset)
+                    .get_result::<Self::ModelWithId>(connection)
+                    .map_err(ResourceError::from)
+            }
+        }};
+
+        let body = if hooks(input) {
+            hooked_write(input, "before_update", "after_update", write)?
+        } else {
+            let prepare = Prepare.build(input)?;
+            quote! {
+                #prepare
+                let connection = #connection;
+                connection.transaction::<_, ResourceError, _>(|| #write)
+            }
+        };
+
+        Ok(quote! {
+            impl ResourceVersioned for #controller {
+                type Version = #ty;
+
+                fn update_versioned(
+                    &self,
+                    model: &Self::Model,
+                    version: Self::Version,
+                    by: Expr<#schema::table>,
+                ) -> Result<Self::ModelWithId, ResourceError> {
+                    #body
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,238 @@
+use crate::ResourceError;
+
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    pg::Pg,
+    row::Row,
+};
+use std::sync::RwLock;
+
+/// Encrypts and decrypts the values of `#[encrypted]` fields, registered with `set_key_provider`.
+pub trait KeyProvider: Send + Sync {
+    /// The id of the key new values are encrypted with, stored as a prefix of each value so that
+    /// values encrypted with older keys can still be decrypted.
+    fn current_key_id(&self) -> String;
+    fn encrypt(&self, key_id: &str, plaintext: &[u8]) -> Result<Vec<u8>, ResourceError>;
+    fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>, ResourceError>;
+}
+
+static KEY_PROVIDER: RwLock<Option<Box<dyn KeyProvider>>> = RwLock::new(None);
+
+pub fn set_key_provider<P: KeyProvider + 'static>(provider: P) {
+    *KEY_PROVIDER.write().expect("Key provider lock poisoned") = Some(Box::new(provider));
+}
+
+fn with_key_provider<T>(
+    f: impl FnOnce(&dyn KeyProvider) -> Result<T, ResourceError>,
+) -> Result<T, ResourceError> {
+    let provider = KEY_PROVIDER.read().expect("Key provider lock poisoned");
+    match *provider {
+        Some(ref provider) => f(provider.as_ref()),
+        None => Err(ResourceError::Encryption(String::from("no key provider set"))),
+    }
+}
+
+/// Encrypts `plaintext` with the current key, as `<key id>:<hex ciphertext>`.
+pub fn encrypt_value(plaintext: &str) -> Result<String, ResourceError> {
+    with_key_provider(|provider| {
+        let key_id = provider.current_key_id();
+        let ciphertext = provider.encrypt(&key_id, plaintext.as_bytes())?;
+        Ok(format!("{}:{}", key_id, encode_hex(&ciphertext)))
+    })
+}
+
+/// Decrypts a value produced by `encrypt_value`, with the key named by its prefix.
+pub fn decrypt_value(value: &str) -> Result<String, ResourceError> {
+    let malformed = || ResourceError::Encryption(String::from("malformed encrypted value"));
+
+    let separator = value.rfind(':').ok_or_else(malformed)?;
+    let (key_id, hex) = (&value[..separator], &value[separator + 1..]);
+    let ciphertext = decode_hex(hex).ok_or_else(malformed)?;
+
+    with_key_provider(|provider| {
+        let plaintext = provider.decrypt(key_id, &ciphertext)?;
+        String::from_utf8(plaintext).map_err(|_| malformed())
+    })
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
+
+/// A column decrypted through `decrypt_value`, used in the `Queryable` row of `#[encrypted]`
+/// fields, so that values which can't be decrypted fail the query instead of the process.
+pub struct Encrypted(pub String);
+
+impl<ST> FromSqlRow<ST, Pg> for Encrypted
+where
+    String: FromSql<ST, Pg>,
+{
+    fn build_from_row<R: Row<Pg>>(row: &mut R) -> deserialize::Result<Self> {
+        let value = String::from_sql(row.take())?;
+        decrypt_value(&value).map(Encrypted).map_err(Into::into)
+    }
+}
+
+/// AES-256-GCM keys by id, with a random nonce prepended to each ciphertext.
+#[cfg(feature = "encryption")]
+pub struct AesGcmKeys {
+    current: String,
+    keys: std::collections::HashMap<String, [u8; 32]>,
+}
+
+#[cfg(feature = "encryption")]
+impl AesGcmKeys {
+    pub fn new(key_id: &str, key: [u8; 32]) -> Self {
+        let mut keys = std::collections::HashMap::new();
+        keys.insert(key_id.to_string(), key);
+        AesGcmKeys { current: key_id.to_string(), keys }
+    }
+
+    /// Adds a retired key, still used to decrypt values encrypted with it.
+    pub fn with_key(mut self, key_id: &str, key: [u8; 32]) -> Self {
+        self.keys.insert(key_id.to_string(), key);
+        self
+    }
+
+    fn cipher(&self, key_id: &str) -> Result<aes_gcm::Aes256Gcm, ResourceError> {
+        use aes_gcm::aead::NewAead;
+
+        match self.keys.get(key_id) {
+            Some(key) => aes_gcm::Aes256Gcm::new_varkey(key)
+                .map_err(|_| ResourceError::Encryption(format!("invalid key {}", key_id))),
+            None => Err(ResourceError::Encryption(format!("unknown key {}", key_id))),
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl KeyProvider for AesGcmKeys {
+    fn current_key_id(&self) -> String {
+        self.current.clone()
+    }
+
+    fn encrypt(&self, key_id: &str, plaintext: &[u8]) -> Result<Vec<u8>, ResourceError> {
+        use aes_gcm::aead::Aead;
+
+        let nonce: [u8; 12] = rand::random();
+        let ciphertext = self
+            .cipher(key_id)?
+            .encrypt(nonce[..].into(), plaintext)
+            .map_err(|_| ResourceError::Encryption(String::from("unable to encrypt")))?;
+
+        Ok(nonce.iter().cloned().chain(ciphertext).collect())
+    }
+
+    fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>, ResourceError> {
+        use aes_gcm::aead::Aead;
+
+        if ciphertext.len() < 12 {
+            return Err(ResourceError::Encryption(String::from("malformed encrypted value")));
+        }
+
+        let (nonce, ciphertext) = ciphertext.split_at(12);
+        self.cipher(key_id)?
+            .decrypt(nonce.into(), ciphertext)
+            .map_err(|_| ResourceError::Encryption(String::from("unable to decrypt")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Xors bytes with the number of the key, `k1` or `k2`.
+    struct XorKeys(&'static str);
+
+    impl KeyProvider for XorKeys {
+        fn current_key_id(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn encrypt(&self, key_id: &str, plaintext: &[u8]) -> Result<Vec<u8>, ResourceError> {
+            let key = match key_id {
+                "k1" => 1,
+                "k2" => 2,
+                _ => return Err(ResourceError::Encryption(format!("unknown key {}", key_id))),
+            };
+            Ok(plaintext.iter().map(|byte| byte ^ key).collect())
+        }
+
+        fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>, ResourceError> {
+            self.encrypt(key_id, ciphertext)
+        }
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(encode_hex(&[0, 15, 255]), "000fff");
+        assert_eq!(decode_hex("000fff"), Some(vec![0, 15, 255]));
+        assert_eq!(decode_hex("000FFF"), Some(vec![0, 15, 255]));
+        assert_eq!(decode_hex(""), Some(vec![]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+        assert_eq!(decode_hex("é0"), None);
+    }
+
+    // The key provider is global, so its states are checked in order in a single test
+    #[test]
+    fn encrypted_values() {
+        *KEY_PROVIDER.write().unwrap() = None;
+        match encrypt_value("secret") {
+            Err(ResourceError::Encryption(_)) => {}
+            other => panic!("expected an encryption error, got {:?}", other),
+        }
+
+        set_key_provider(XorKeys("k1"));
+        let old = encrypt_value("secret").unwrap();
+        assert!(old.starts_with("k1:"));
+        assert_ne!(old, "k1:secret");
+        assert_eq!(decrypt_value(&old).unwrap(), "secret");
+
+        // Values encrypted with a rotated key remain readable
+        set_key_provider(XorKeys("k2"));
+        let new = encrypt_value("secret").unwrap();
+        assert!(new.starts_with("k2:"));
+        assert_eq!(decrypt_value(&old).unwrap(), "secret");
+        assert_eq!(decrypt_value(&new).unwrap(), "secret");
+
+        for malformed in &["secret", "k1:abc", "k1:zz", "k3:00", "k1:ff"] {
+            match decrypt_value(malformed) {
+                Err(ResourceError::Encryption(_)) => {}
+                other => panic!("expected an encryption error for {}, got {:?}", malformed, other),
+            }
+        }
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn aes_gcm_keys() {
+        let keys = AesGcmKeys::new("new", [2; 32]).with_key("old", [1; 32]);
+        assert_eq!(keys.current_key_id(), "new");
+
+        let ciphertext = keys.encrypt("new", b"secret").unwrap();
+        assert_ne!(&ciphertext[12..], b"secret");
+        assert_eq!(keys.decrypt("new", &ciphertext).unwrap(), b"secret");
+        assert!(keys.decrypt("old", &ciphertext).is_err());
+        assert!(keys.decrypt("gone", &ciphertext).is_err());
+
+        let mut tampered = ciphertext.clone();
+        tampered[12] ^= 1;
+        assert!(keys.decrypt("new", &tampered).is_err());
+        assert!(keys.decrypt("new", &ciphertext[..8]).is_err());
+
+        let old = AesGcmKeys::new("old", [1; 32]).encrypt("old", b"secret").unwrap();
+        assert_eq!(keys.decrypt("old", &old).unwrap(), b"secret");
+    }
+}
@@ -0,0 +1,110 @@
+// `diesel_infix_operator!` nests the impls of each operator in a `const` item.
+#![allow(non_local_definitions)]
+
+use diesel::{
+    dsl::any,
+    expression::{operators::Eq, AsExpression, Expression},
+    pg::{expression::array_comparison::Any, Pg},
+    sql_types::{Array, Cidr, Inet, Jsonb, Nullable, Range, Text},
+};
+
+diesel_infix_operator!(JsonbContains, " @> ", backend: Pg);
+diesel_infix_operator!(JsonbHasKey, " ? ", backend: Pg);
+diesel_infix_operator!(RangeContains, " @> ", backend: Pg);
+diesel_infix_operator!(RangeOverlaps, " && ", backend: Pg);
+diesel_infix_operator!(NetworkWithin, " <<= ", backend: Pg);
+diesel_infix_operator!(NetworkContains, " >>= ", backend: Pg);
+
+/// SQL types of `jsonb` columns, nullable or not.
+pub trait JsonbType {}
+
+impl JsonbType for Jsonb {}
+impl JsonbType for Nullable<Jsonb> {}
+
+pub trait JsonbExpressionMethods: Expression + Sized {
+    /// `self @> other`
+    fn contains_json<T>(self, other: T) -> JsonbContains<Self, T::Expression>
+    where
+        T: AsExpression<Self::SqlType>,
+    {
+        JsonbContains::new(self, other.as_expression())
+    }
+
+    /// `self ? key`
+    fn has_key<T>(self, key: T) -> JsonbHasKey<Self, T::Expression>
+    where
+        T: AsExpression<Text>,
+    {
+        JsonbHasKey::new(self, key.as_expression())
+    }
+}
+
+impl<T> JsonbExpressionMethods for T
+where
+    T: Expression,
+    T::SqlType: JsonbType,
+{
+}
+
+pub trait ArrayExpressionMethods<ST>: Expression<SqlType = Array<ST>> + Sized {
+    /// `value = ANY(self)`
+    fn any_eq<T>(self, value: T) -> Eq<T::Expression, Any<Self>>
+    where
+        T: AsExpression<ST>,
+    {
+        Eq::new(value.as_expression(), any(self))
+    }
+}
+
+impl<T, ST> ArrayExpressionMethods<ST> for T where T: Expression<SqlType = Array<ST>> {}
+
+pub trait RangeExpressionMethods<ST>: Expression<SqlType = Range<ST>> + Sized {
+    /// `self @> value`
+    fn contains_value<T>(self, value: T) -> RangeContains<Self, T::Expression>
+    where
+        T: AsExpression<ST>,
+    {
+        RangeContains::new(self, value.as_expression())
+    }
+
+    /// `self && other`
+    fn overlaps_range<T>(self, other: T) -> RangeOverlaps<Self, T::Expression>
+    where
+        T: AsExpression<Range<ST>>,
+    {
+        RangeOverlaps::new(self, other.as_expression())
+    }
+}
+
+impl<T, ST> RangeExpressionMethods<ST> for T where T: Expression<SqlType = Range<ST>> {}
+
+/// SQL types of `inet` and `cidr` columns.
+pub trait NetworkType {}
+
+impl NetworkType for Inet {}
+impl NetworkType for Cidr {}
+
+pub trait NetworkExpressionMethods: Expression + Sized {
+    /// `self <<= other`
+    fn within_network<T>(self, other: T) -> NetworkWithin<Self, T::Expression>
+    where
+        T: AsExpression<Self::SqlType>,
+    {
+        NetworkWithin::new(self, other.as_expression())
+    }
+
+    /// `self >>= other`
+    fn contains_network<T>(self, other: T) -> NetworkContains<Self, T::Expression>
+    where
+        T: AsExpression<Self::SqlType>,
+    {
+        NetworkContains::new(self, other.as_expression())
+    }
+}
+
+impl<T> NetworkExpressionMethods for T
+where
+    T: Expression,
+    T::SqlType: NetworkType,
+{
+}
@@ -0,0 +1,136 @@
+// The diesel derives wrap their impls in a `const` item, which this lint reports.
+#![allow(non_local_definitions)]
+
+use crate::ResourceError;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::Pg,
+    serialize::{self, Output, ToSql},
+    sql_types::Text,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::io::Write;
+
+/// A password hashing scheme, named in snake case by `#[hashed(...)]` fields.
+pub trait PasswordHasher {
+    fn hash(password: &str) -> Result<String, ResourceError>;
+    fn verify(hash: &str, password: &str) -> Result<bool, ResourceError>;
+}
+
+/// The value of a `#[hashed]` field. Writes replace a `Plain` password with its hash and keep a
+/// `Hashed` one as is, which is what reads return, so that a model read back can be updated
+/// without hashing its hash again.
+#[derive(FromSqlRow, AsExpression, Clone, PartialEq, Eq)]
+#[sql_type = "Text"]
+pub enum Password {
+    Plain(String),
+    Hashed(String),
+}
+
+impl Password {
+    /// Hashes a plaintext password with `H`.
+    pub fn into_hashed<H: PasswordHasher>(self) -> Result<Self, ResourceError> {
+        match self {
+            Password::Plain(password) => H::hash(&password).map(Password::Hashed),
+            hashed => Ok(hashed),
+        }
+    }
+
+    /// Whether `candidate` matches the hash, always false for a plaintext.
+    pub fn verify<H: PasswordHasher>(&self, candidate: &str) -> Result<bool, ResourceError> {
+        match self {
+            Password::Plain(_) => Ok(false),
+            Password::Hashed(hash) => H::verify(hash, candidate),
+        }
+    }
+}
+
+impl From<String> for Password {
+    fn from(password: String) -> Self {
+        Password::Plain(password)
+    }
+}
+
+impl<'a> From<&'a str> for Password {
+    fn from(password: &'a str) -> Self {
+        Password::Plain(password.to_string())
+    }
+}
+
+impl std::fmt::Debug for Password {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Password::Plain(_) => f.debug_tuple("Plain").field(&"[redacted]").finish(),
+            Password::Hashed(_) => f.debug_tuple("Hashed").field(&"[redacted]").finish(),
+        }
+    }
+}
+
+/// Serialized as the string it holds.
+impl Serialize for Password {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Password::Plain(value) | Password::Hashed(value) => serializer.serialize_str(value),
+        }
+    }
+}
+
+/// Deserialized as a plaintext, since hashes are only read from the database.
+impl<'de> Deserialize<'de> for Password {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Password::Plain)
+    }
+}
+
+impl ToSql<Text, Pg> for Password {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match self {
+            Password::Plain(_) => {
+                Err("a plaintext password must be hashed before it is written".into())
+            }
+            Password::Hashed(hash) => ToSql::<Text, Pg>::to_sql(hash, out),
+        }
+    }
+}
+
+impl FromSql<Text, Pg> for Password {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        FromSql::<Text, Pg>::from_sql(bytes).map(Password::Hashed)
+    }
+}
+
+/// Argon2 hashes in their encoded form, with a random salt for each password.
+#[cfg(feature = "argon2")]
+pub struct Argon2;
+
+#[cfg(feature = "argon2")]
+impl PasswordHasher for Argon2 {
+    fn hash(password: &str) -> Result<String, ResourceError> {
+        let salt: [u8; 16] = rand::random();
+        argon2::hash_encoded(password.as_bytes(), &salt, &argon2::Config::default())
+            .map_err(|error| ResourceError::Hash(error.to_string()))
+    }
+
+    fn verify(hash: &str, password: &str) -> Result<bool, ResourceError> {
+        argon2::verify_encoded(hash, password.as_bytes())
+            .map_err(|error| ResourceError::Hash(error.to_string()))
+    }
+}
+
+#[cfg(all(test, feature = "argon2"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argon2_hashes_are_verified() {
+        let hash = Argon2::hash("hunter2").unwrap();
+        assert!(Argon2::verify(&hash, "hunter2").unwrap());
+        assert!(!Argon2::verify(&hash, "hunter3").unwrap());
+
+        // A plaintext which looks like a hash is still hashed
+        let hashed = Password::from(hash.as_str()).into_hashed::<Argon2>().unwrap();
+        assert_ne!(hashed, Password::Hashed(hash.clone()));
+        assert!(hashed.verify::<Argon2>(&hash).unwrap());
+    }
+}
@@ -1,7 +1,24 @@
-pub use postgres_resource_derive::resource;
+#[macro_use]
+extern crate diesel;
+
+pub use postgres_resource_derive::{resource, resource_enum};
+
+mod encryption;
+mod filter;
+mod hash;
+mod normalize;
+mod types;
+mod validation;
+
+#[cfg(test)]
+mod test;
+
+pub use crate::{encryption::*, filter::*, hash::*, normalize::*, types::*, validation::*};
+
+#[cfg(test)]
+use crate::test::schema;
 
 use diesel::{
-    self,
     expression::BoxableExpression,
     pg::{Pg, PgConnection},
     prelude::*,
@@ -31,9 +48,70 @@ pub trait ResourceWithId {
     type ModelWithId: Queryable<Self::SQLType, Pg>;
 }
 
+/// A Postgres enum generated by `#[resource_enum]`.
+pub trait ResourceEnum {
+    const TYPE_NAME: &'static str;
+}
+
+/// Whether `type_name` is the type name of a `ResourceEnum`, checked at compile time for the
+/// columns whose type is inferred from their enum.
+#[doc(hidden)]
+pub const fn is_type_name(type_name: &str, expected: &str) -> bool {
+    let (type_name, expected) = (type_name.as_bytes(), expected.as_bytes());
+    if type_name.len() != expected.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < type_name.len() {
+        if type_name[i] != expected[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
 pub type Expr<T> = Box<BoxableExpression<T, Pg, SqlType = Bool>>;
 
-type Result<T> = std::result::Result<T, Error>;
+#[derive(Debug)]
+pub enum ResourceError {
+    Database(Error),
+    Conflict,
+    Hash(String),
+    Encryption(String),
+    Validation(ValidationErrors),
+    MissingField(&'static str),
+}
+
+impl From<Error> for ResourceError {
+    fn from(error: Error) -> Self {
+        ResourceError::Database(error)
+    }
+}
+
+impl From<ValidationErrors> for ResourceError {
+    fn from(errors: ValidationErrors) -> Self {
+        ResourceError::Validation(errors)
+    }
+}
+
+impl std::fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ResourceError::Database(error) => write!(f, "{}", error),
+            ResourceError::Conflict => write!(f, "the row was modified by another update"),
+            ResourceError::Hash(error) => write!(f, "unable to hash the password: {}", error),
+            ResourceError::Encryption(error) => write!(f, "encryption failed: {}", error),
+            ResourceError::Validation(errors) => write!(f, "validation failed: {}", errors),
+            ResourceError::MissingField(field) => write!(f, "missing required field {}", field),
+        }
+    }
+}
+
+impl std::error::Error for ResourceError {}
+
+type Result<T> = std::result::Result<T, ResourceError>;
 
 pub trait ResourceController
 where
@@ -42,6 +120,94 @@ where
     fn create(&self, model: &Self::Model) -> Result<Self::ModelWithId>;
     fn get_one(&self, by: Expr<Self::Table>) -> Result<Self::ModelWithId>;
     fn get_all(&self, by: Expr<Self::Table>) -> Result<Vec<Self::ModelWithId>>;
-    fn update(&self, model: &Self::Model, by: Expr<Self::Table>) -> Result<Self::ModelWithId>;
     fn delete(&self, by: Expr<Self::Table>) -> Result<usize>;
+    fn count(&self, by: Expr<Self::Table>) -> Result<i64>;
+}
+
+/// Updates of resources without a `#[version]`, which go through `ResourceVersioned` instead.
+pub trait ResourceUpdate
+where
+    Self: ResourceController,
+{
+    fn update(&self, model: &Self::Model, by: Expr<Self::Table>) -> Result<Self::ModelWithId>;
+}
+
+pub trait ResourceSoftDelete
+where
+    Self: ResourceController,
+{
+    fn with_deleted(&self, by: Expr<Self::Table>) -> Result<Vec<Self::ModelWithId>>;
+    fn restore(&self, by: Expr<Self::Table>) -> Result<usize>;
+    fn purge(&self, by: Expr<Self::Table>) -> Result<usize>;
+}
+
+pub trait ResourceVersioned
+where
+    Self: ResourceController,
+{
+    type Version;
+
+    fn update_versioned(
+        &self,
+        model: &Self::Model,
+        version: Self::Version,
+        by: Expr<Self::Table>,
+    ) -> Result<Self::ModelWithId>;
+}
+
+/// Callbacks run by the controllers of `#[hooks]` resources in the transaction of each write.
+/// Before-hooks may change the model or abort the write by returning an error.
+pub trait ResourceHooks
+where
+    Self: Resource + ResourceWithId,
+{
+    fn before_create(&self, _connection: &PgConnection, _model: &mut Self::Model) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_create(&self, _connection: &PgConnection, _created: &Self::ModelWithId) -> Result<()> {
+        Ok(())
+    }
+
+    fn before_update(&self, _connection: &PgConnection, _model: &mut Self::Model) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_update(&self, _connection: &PgConnection, _updated: &Self::ModelWithId) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called with the rows about to be deleted.
+    fn before_delete(
+        &self,
+        _connection: &PgConnection,
+        _models: &[Self::ModelWithId],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_delete(
+        &self,
+        _connection: &PgConnection,
+        _deleted: &[Self::ModelWithId],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called with the soft deleted rows about to be restored.
+    fn before_restore(
+        &self,
+        _connection: &PgConnection,
+        _models: &[Self::ModelWithId],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_restore(
+        &self,
+        _connection: &PgConnection,
+        _restored: &[Self::ModelWithId],
+    ) -> Result<()> {
+        Ok(())
+    }
 }
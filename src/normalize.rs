@@ -0,0 +1,50 @@
+/// A step of `#[normalize(...)]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Normalize {
+    Trim,
+    Lowercase,
+    Uppercase,
+}
+
+/// Applies the `steps`, in order, to `value`.
+pub fn normalize(value: &str, steps: &[Normalize]) -> String {
+    steps.iter().fold(value.to_string(), |value, step| match step {
+        Normalize::Trim => value.trim().to_string(),
+        Normalize::Lowercase => value.to_lowercase(),
+        Normalize::Uppercase => value.to_uppercase(),
+    })
+}
+
+/// Lowercases `value` and joins its alphanumeric runs with dashes, as in `hello-world`.
+pub fn slugify(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_are_applied_in_order() {
+        assert_eq!(normalize("  Alice ", &[]), "  Alice ");
+        assert_eq!(normalize("  Alice ", &[Normalize::Trim]), "Alice");
+        assert_eq!(normalize("  Alice ", &[Normalize::Trim, Normalize::Lowercase]), "alice");
+        assert_eq!(normalize("straße", &[Normalize::Uppercase]), "STRASSE");
+        assert_eq!(normalize("ALICE", &[Normalize::Uppercase, Normalize::Lowercase]), "alice");
+    }
+
+    #[test]
+    fn slugs_join_words_with_dashes() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Read   Only  "), "read-only");
+        assert_eq!(slugify("Crème Brûlée 2"), "crème-brûlée-2");
+        assert_eq!(slugify("already-a-slug"), "already-a-slug");
+        assert_eq!(slugify("--!!--"), "");
+        assert_eq!(slugify(""), "");
+    }
+}
@@ -1,5 +1,91 @@
-use diesel::types::Uuid;
-use postgres_resource_derive::resource;
+use crate::*;
+
+use self::schema::*;
+use diesel::{delete, insert_into, prelude::*, update};
+use serde_derive::{Deserialize, Serialize};
+use std::result::Result;
+use uuid::Uuid;
+
+pub mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            uuid -> Nullable<Uuid>,
+            username -> Nullable<Text>,
+            password -> Nullable<Text>,
+            email -> Nullable<Text>,
+            verification_id -> Nullable<Int4>,
+        }
+    }
+
+    table! {
+        codes (id) {
+            id -> Int4,
+            code -> Text,
+        }
+    }
+
+    table! {
+        plans (id) {
+            id -> Int4,
+            name -> Text,
+            seats -> Int4,
+            discount -> Int4,
+        }
+    }
+
+    table! {
+        tokens (id) {
+            id -> Int4,
+            label -> Text,
+            secret -> Text,
+        }
+    }
+
+    table! {
+        verifications (id) {
+            id -> Int4,
+            code -> Text,
+        }
+    }
+}
+
+/// Prefixes passwords with `hashed:`, so that tests can tell hashes apart without a real scheme.
+pub struct Prefixed;
+
+impl PasswordHasher for Prefixed {
+    fn hash(password: &str) -> Result<String, ResourceError> {
+        Ok(format!("hashed:{}", password))
+    }
+
+    fn verify(hash: &str, password: &str) -> Result<bool, ResourceError> {
+        Ok(hash == Self::hash(password)?)
+    }
+}
+
+#[resource]
+struct Plan {
+    name: String,
+
+    #[default = 5]
+    seats: i32,
+
+    #[db_default]
+    discount: i32,
+}
+
+#[resource]
+struct Token {
+    label: String,
+
+    #[sensitive]
+    secret: String,
+}
+
+#[resource]
+struct Verification {
+    code: String,
+}
 
 #[resource(schema = crate::schema::accounts, table = "accounts")]
 struct Account {
@@ -7,18 +93,124 @@ struct Account {
     uuid: Uuid,
 
     #[optional]
+    #[validate(length(min = 3, max = 32))]
     username: String,
 
     #[optional]
-    password: String,
+    #[hashed(prefixed)]
+    password: Password,
 
     #[optional]
+    #[validate(email)]
     email: String,
 
     #[optional]
-    #[fk]
-    verification_id: i32,
+    #[fk(Verification)]
+    verification_id: VerificationId,
+}
+
+fn account(password: &str) -> Account {
+    Account {
+        uuid: None,
+        username: Some(String::from("alice")),
+        password: Some(Password::from(password)),
+        email: None,
+    }
 }
 
 #[test]
 fn test() {}
+
+#[test]
+fn hashed_fields_are_hashed_once() {
+    let hashed = |password: &str| Some(Password::Hashed(password.to_string()));
+
+    let created = AccountController::prepare_model(account("hunter2")).unwrap();
+    assert_eq!(created.password, hashed("hashed:hunter2"));
+
+    // Updating a model read back keeps its hash
+    let updated = AccountController::prepare_model(created).unwrap();
+    assert_eq!(updated.password, hashed("hashed:hunter2"));
+
+    // A plaintext is hashed even when it looks like a hash
+    let changed = AccountController::prepare_model(account("hashed:hunter3")).unwrap();
+    assert_eq!(changed.password, hashed("hashed:hashed:hunter3"));
+}
+
+#[test]
+fn models_are_validated() {
+    assert_eq!(account("hunter2").validate(), Ok(()));
+
+    let invalid = Account {
+        username: Some(String::from("al")),
+        email: Some(String::from("alice")),
+        ..account("hunter2")
+    };
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.field_errors()["email"], vec![ValidationError::Email]);
+    assert_eq!(
+        errors.field_errors()["username"][0],
+        ValidationError::Length { min: Some(3), max: Some(32) }
+    );
+
+    // Omitted optional fields aren't checked
+    let omitted = Account { username: None, email: None, ..account("hunter2") };
+    assert_eq!(omitted.validate(), Ok(()));
+
+    match AccountController::prepare_model(invalid) {
+        Err(ResourceError::Validation(prepared)) => assert_eq!(prepared, errors),
+        other => panic!("expected validation errors, got {:?}", other),
+    }
+}
+
+#[test]
+fn sensitive_fields_are_hidden() {
+    let token = Token { label: String::from("ci"), secret: String::from("s3cr3t") };
+    assert_eq!(format!("{:?}", token), r#"Token { label: "ci", secret: "[redacted]" }"#);
+    assert_eq!(serde_json::to_string(&token).unwrap(), r#"{"label":"ci"}"#);
+
+    let with_id = TokenWithId { id: TokenId(1), inner: token };
+    assert!(!format!("{:?}", with_id).contains("s3cr3t"));
+    assert!(!serde_json::to_string(&with_id).unwrap().contains("s3cr3t"));
+}
+
+#[test]
+fn builders_check_required_fields() {
+    match Plan::builder().seats(10).build() {
+        Err(ResourceError::MissingField(field)) => assert_eq!(field, "name"),
+        other => panic!("expected a missing field, got {:?}", other),
+    }
+
+    // Defaults fill the fields which aren't set
+    let plan = Plan::builder().name("team").build().unwrap();
+    assert_eq!((plan.name.as_str(), plan.seats, plan.discount), ("team", 5, None));
+
+    let plan = Plan::builder().name(String::from("team")).seats(10).discount(20).build().unwrap();
+    assert_eq!((plan.seats, plan.discount), (10, Some(20)));
+}
+
+#[cfg(feature = "regex")]
+mod regex {
+    use super::*;
+
+    #[resource]
+    struct Code {
+        #[validate(regex = "^[0-9]{6}$")]
+        code: String,
+    }
+
+    #[test]
+    fn regex_validations_match_the_pattern() {
+        let code = |code: &str| Code { code: code.to_string() };
+        assert_eq!(code("123456").validate(), Ok(()));
+
+        let errors = code("12345a").validate().unwrap_err();
+        assert_eq!(
+            errors.field_errors()["code"],
+            vec![ValidationError::Regex { pattern: "^[0-9]{6}$" }]
+        );
+
+        // The compiled pattern is reused by later checks
+        assert_eq!(code("654321").validate(), Ok(()));
+    }
+}
@@ -0,0 +1,156 @@
+// The diesel derives on these types emit their impls inside `const` items.
+#![allow(non_local_definitions)]
+
+use chrono::{DateTime, Utc};
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    pg::{data_types::PgInterval, Pg},
+    row::Row,
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::{self, Jsonb},
+    AsExpression,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::Bound,
+    fmt::Debug,
+    io::Write,
+    ops::{Deref, DerefMut},
+};
+
+/// A typed payload stored in a `jsonb` column. `#[jsonb]` fields of type `T` are generated as
+/// `Json<T>`, which dereferences to `T` and serializes as `T` does.
+#[derive(Serialize, Deserialize, FromSqlRow, AsExpression, Clone, Debug, Default, PartialEq)]
+#[serde(transparent)]
+#[sql_type = "Jsonb"]
+pub struct Json<T>(pub T);
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> ToSql<Jsonb, Pg> for Json<T>
+where
+    T: Serialize + Debug,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        out.write_all(&[1])?;
+        serde_json::to_writer(out, &self.0).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl<T> FromSql<Jsonb, Pg> for Json<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        match bytes {
+            Some(bytes) if bytes.first() == Some(&1) => {
+                serde_json::from_slice(&bytes[1..]).map(Json).map_err(Into::into)
+            }
+            Some(_) => Err("Unsupported JSONB encoding version".into()),
+            None => Err("Unexpected null for non-null column".into()),
+        }
+    }
+}
+
+/// A `tstzrange` value.
+pub type TstzRange = (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>);
+
+/// An `interval` value, serializable unlike `PgInterval`.
+#[derive(
+    Serialize, Deserialize, FromSqlRow, AsExpression, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[sql_type = "sql_types::Interval"]
+pub struct Interval {
+    pub microseconds: i64,
+    pub days: i32,
+    pub months: i32,
+}
+
+impl ToSql<sql_types::Interval, Pg> for Interval {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let interval = PgInterval::new(self.microseconds, self.days, self.months);
+        ToSql::<sql_types::Interval, Pg>::to_sql(&interval, out)
+    }
+}
+
+impl FromSql<sql_types::Interval, Pg> for Interval {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let interval: PgInterval = FromSql::<sql_types::Interval, Pg>::from_sql(bytes)?;
+        Ok(Interval {
+            microseconds: interval.microseconds,
+            days: interval.days,
+            months: interval.months,
+        })
+    }
+}
+
+/// The `citext` extension type.
+#[derive(SqlType, QueryId)]
+#[postgres(type_name = "citext")]
+pub struct Citext;
+
+/// A case-insensitive `citext` value.
+#[derive(
+    Serialize, Deserialize, FromSqlRow, AsExpression, Clone, Debug, Default, PartialEq, Eq, Hash,
+)]
+#[serde(transparent)]
+#[sql_type = "Citext"]
+pub struct CiString(pub String);
+
+impl Deref for CiString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for CiString {
+    fn from(string: String) -> Self {
+        CiString(string)
+    }
+}
+
+impl<'a> From<&'a str> for CiString {
+    fn from(string: &'a str) -> Self {
+        CiString(string.to_string())
+    }
+}
+
+impl ToSql<Citext, Pg> for CiString {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        ToSql::<sql_types::Text, Pg>::to_sql(&self.0, out)
+    }
+}
+
+impl FromSql<Citext, Pg> for CiString {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        FromSql::<sql_types::Text, Pg>::from_sql(bytes).map(CiString)
+    }
+}
+
+/// A column read through `FromSql` alone, used in the `Queryable` row of `#[sql_type]` fields.
+/// Writing the field still needs `ToSql` and `AsExpression` for the SQL type of the column.
+pub struct FromSqlValue<T>(pub T);
+
+impl<T, ST> FromSqlRow<ST, Pg> for FromSqlValue<T>
+where
+    T: FromSql<ST, Pg>,
+{
+    fn build_from_row<R: Row<Pg>>(row: &mut R) -> deserialize::Result<Self> {
+        T::from_sql(row.take()).map(FromSqlValue)
+    }
+}
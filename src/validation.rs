@@ -0,0 +1,193 @@
+use crate::CiString;
+
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+
+/// A failed `#[validate(...)]` check, with the bounds it was checked against.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum ValidationError {
+    Length { min: Option<usize>, max: Option<usize> },
+    Email,
+    Regex { pattern: &'static str },
+    Range { min: Option<f64>, max: Option<f64> },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ValidationError::Length { min, max } => {
+                write!(f, "length out of bounds {:?}..{:?}", min, max)
+            }
+            ValidationError::Email => write!(f, "invalid email address"),
+            ValidationError::Regex { pattern } => write!(f, "does not match {}", pattern),
+            ValidationError::Range { min, max } => {
+                write!(f, "value out of bounds {:?}..{:?}", min, max)
+            }
+        }
+    }
+}
+
+/// The failed checks of a model, by field.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct ValidationErrors(BTreeMap<&'static str, Vec<ValidationError>>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        ValidationErrors::default()
+    }
+
+    pub fn add(&mut self, field: &'static str, error: ValidationError) {
+        self.0.entry(field).or_default().push(error);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn field_errors(&self) -> &BTreeMap<&'static str, Vec<ValidationError>> {
+        &self.0
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut fields = self.0.iter().peekable();
+        while let Some((field, errors)) = fields.next() {
+            let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+            write!(f, "{}: {}", field, errors.join(", "))?;
+            if fields.peek().is_some() {
+                write!(f, "; ")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Values checked by `#[validate(length(...))]`, measured in characters for strings.
+pub trait ValidateLength {
+    fn validate_length(&self) -> usize;
+}
+
+impl ValidateLength for String {
+    fn validate_length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl ValidateLength for CiString {
+    fn validate_length(&self) -> usize {
+        self.0.validate_length()
+    }
+}
+
+impl<T> ValidateLength for Vec<T> {
+    fn validate_length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Whether `value` looks like an email address: a local part and a dotted domain, without spaces.
+pub fn validate_email(value: &str) -> bool {
+    let mut parts = value.splitn(2, '@');
+    match (parts.next(), parts.next()) {
+        (Some(local), Some(domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && !value.chars().any(char::is_whitespace)
+                && domain.split('.').count() > 1
+                && domain.split('.').all(|label| !label.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// The pattern of a `#[validate(regex = "...")]` check, compiled on first use and kept for the
+/// following ones. The pattern is checked when the resource is expanded.
+#[cfg(feature = "regex")]
+pub struct ValidationRegex {
+    pattern: &'static str,
+    regex: once_cell::sync::OnceCell<regex::Regex>,
+}
+
+#[cfg(feature = "regex")]
+impl ValidationRegex {
+    pub const fn new(pattern: &'static str) -> Self {
+        ValidationRegex { pattern, regex: once_cell::sync::OnceCell::new() }
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex
+            .get_or_init(|| {
+                regex::Regex::new(self.pattern).expect("invalid #[validate(regex)] pattern")
+            })
+            .is_match(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emails_are_validated() {
+        assert!(validate_email("alice@example.com"));
+        assert!(validate_email("alice+tag@mail.example.com"));
+
+        assert!(!validate_email("alice"));
+        assert!(!validate_email("@example.com"));
+        assert!(!validate_email("alice@example"));
+        assert!(!validate_email("alice@example..com"));
+        assert!(!validate_email("alice@@example.com"));
+        assert!(!validate_email("alice smith@example.com"));
+    }
+
+    #[test]
+    fn errors_are_grouped_by_field() {
+        let mut errors = ValidationErrors::new();
+        assert!(errors.is_empty());
+        assert_eq!(errors.clone().into_result(), Ok(()));
+
+        errors.add("username", ValidationError::Length { min: Some(3), max: None });
+        errors.add("email", ValidationError::Email);
+        errors.add("username", ValidationError::Regex { pattern: "^[a-z]+$" });
+
+        assert_eq!(errors.field_errors()["username"].len(), 2);
+        assert_eq!(
+            errors.to_string(),
+            "email: invalid email address; username: length out of bounds Some(3)..None, does not match ^[a-z]+$"
+        );
+        assert_eq!(
+            serde_json::to_string(&errors).unwrap(),
+            r#"{"email":[{"code":"email"}],"username":[{"code":"length","min":3,"max":null},{"code":"regex","pattern":"^[a-z]+$"}]}"#
+        );
+        assert_eq!(errors.clone().into_result(), Err(errors));
+    }
+
+    #[test]
+    fn lengths_are_counted_in_characters() {
+        assert_eq!(String::from("héllo").validate_length(), 5);
+        assert_eq!(CiString::from("héllo").validate_length(), 5);
+        assert_eq!(vec![1, 2, 3].validate_length(), 3);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regexes_are_matched() {
+        let regex = ValidationRegex::new("^[a-z]+$");
+        assert!(regex.is_match("alice"));
+        assert!(!regex.is_match("Alice"));
+        assert!(regex.is_match("bob"));
+    }
+}
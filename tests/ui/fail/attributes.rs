@@ -0,0 +1,15 @@
+use postgres_resource::*;
+
+#[resource]
+struct Session {
+    #[fk(Account, Verification)]
+    account_id: AccountId,
+}
+
+#[resource]
+struct Account {
+    #[hashed]
+    password: String,
+}
+
+fn main() {}
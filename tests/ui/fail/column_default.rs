@@ -0,0 +1,15 @@
+use postgres_resource::*;
+
+#[resource]
+struct Account {
+    #[generated(default = 1)]
+    uuid: String,
+}
+
+#[resource]
+struct Role {
+    #[db_default(now)]
+    created: String,
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        use diesel::sql_types::*;
+        use crate::AccountStatusType;
+        accounts (id) {
+            id -> Int4,
+            status -> AccountStatusType,
+        }
+    }
+}
+
+#[resource_enum]
+#[type_name = "status"]
+enum AccountStatus {
+    Active,
+    Suspended,
+}
+
+#[resource]
+struct Account {
+    status: AccountStatus,
+}
+
+fn main() {}
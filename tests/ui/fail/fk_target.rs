@@ -0,0 +1,12 @@
+use postgres_resource::*;
+
+#[resource]
+struct Transfer {
+    #[fk(Account)]
+    sender_id: AccountId,
+    #[fk(Account)]
+    recipient_id: AccountId,
+    amount: i64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use postgres_resource::*;
+
+#[resource]
+struct Account {
+    #[hashed(argon2)]
+    password: String,
+}
+
+fn main() {}
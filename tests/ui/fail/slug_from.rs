@@ -0,0 +1,10 @@
+use postgres_resource::*;
+
+#[resource]
+struct Role {
+    name: String,
+    #[slug(from = "title")]
+    slug: String,
+}
+
+fn main() {}
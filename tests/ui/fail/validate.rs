@@ -0,0 +1,15 @@
+use postgres_resource::*;
+
+#[resource]
+struct Account {
+    #[validate(uppercase)]
+    username: String,
+}
+
+#[resource]
+struct Verification {
+    #[validate(length(min = 3, exactly = 4))]
+    code: String,
+}
+
+fn main() {}
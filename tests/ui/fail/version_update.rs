@@ -0,0 +1,33 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            version -> Int4,
+        }
+    }
+}
+
+#[resource]
+struct Account {
+    username: String,
+    #[version]
+    version: i32,
+}
+
+fn updatable<C: ResourceUpdate>(_: C) {}
+
+fn main() {
+    updatable(AccountController);
+}
@@ -0,0 +1,40 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            tags -> Array<Text>,
+            scores -> Nullable<Array<Int4>>,
+        }
+    }
+}
+
+#[resource]
+struct Account {
+    tags: Vec<String>,
+    #[optional]
+    scores: Vec<i32>,
+}
+
+#[allow(dead_code)]
+fn tagged(tag: &str) -> Result<Vec<AccountWithId>, ResourceError> {
+    let tags = vec![tag.to_string()];
+    AccountController.count(AccountFilter::tags_overlaps(tags.clone()))?;
+    AccountController.count(AccountFilter::tags_any(tag.to_string()))?;
+    AccountController.get_all(AccountFilter::tags_contains(tags))
+}
+
+fn main() {
+    assert!(AccountController::CREATE_TABLE.contains("tags TEXT[] NOT NULL"));
+    assert!(!AccountController::CREATE_TABLE.contains("scores INTEGER[] NOT NULL"));
+}
@@ -0,0 +1,40 @@
+#![allow(non_local_definitions)]
+#![allow(non_snake_case)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            usrName -> Text,
+            mail -> Nullable<Text>,
+        }
+    }
+}
+
+#[resource]
+struct Account {
+    #[column = "usrName"]
+    username: String,
+    #[optional]
+    #[column = "mail"]
+    email: String,
+}
+
+#[allow(dead_code)]
+fn by_username(username: &str) -> Result<AccountWithId, ResourceError> {
+    AccountController.get_one(Box::new(accounts::usrName.eq(username.to_string())))
+}
+
+fn main() {
+    assert!(AccountController::CREATE_TABLE.contains("\"usrName\" TEXT NOT NULL"));
+    assert!(AccountController::CREATE_TABLE.contains("mail TEXT"));
+}
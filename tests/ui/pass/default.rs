@@ -0,0 +1,67 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        use diesel::sql_types::*;
+        use crate::AccountTierType;
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            tier -> AccountTierType,
+            weight -> Int4,
+        }
+    }
+
+    table! {
+        settings (id) {
+            id -> Int4,
+            theme -> Text,
+            weight -> Int4,
+            note -> Nullable<Text>,
+        }
+    }
+}
+
+#[resource_enum]
+pub enum AccountTier {
+    Free,
+    Paid,
+}
+
+// `AccountTier` isn't `Default`, so the model is only built through its builder
+#[resource]
+struct Account {
+    username: String,
+    #[default = AccountTier::Free]
+    tier: AccountTier,
+    #[db_default]
+    weight: i32,
+}
+
+#[resource]
+struct Setting {
+    #[default = String::from("dark")]
+    theme: String,
+    #[db_default]
+    weight: i32,
+    #[optional]
+    note: String,
+}
+
+fn main() {
+    let account = Account::builder().username("alice").build().unwrap();
+    assert!(account.weight.is_none());
+
+    let setting = Setting { note: Some(String::from("n")), ..Default::default() };
+    assert_eq!(setting.theme, "dark");
+    assert!(setting.weight.is_none());
+}
@@ -0,0 +1,42 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+        }
+    }
+}
+
+mod models {
+    use super::*;
+
+    /// A user account.
+    #[resource]
+    #[derive(Eq, Hash, PartialOrd)]
+    pub struct Account {
+        username: String,
+    }
+}
+
+use crate::models::*;
+
+fn main() {
+    let mut usernames = HashSet::new();
+    usernames.insert(Account { username: String::from("alice") });
+    assert!(usernames.contains(&Account { username: String::from("alice") }));
+    assert!(Account { username: String::from("a") } < Account { username: String::from("b") });
+
+    let _: AccountController = crate::models::AccountController;
+}
@@ -0,0 +1,57 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            ssn -> Text,
+            notes -> Nullable<Text>,
+        }
+    }
+}
+
+#[resource]
+struct Account {
+    #[encrypted]
+    ssn: String,
+    #[optional]
+    #[encrypted]
+    notes: String,
+}
+
+/// Keys which leave values as they are.
+struct PlainKeys;
+
+impl KeyProvider for PlainKeys {
+    fn current_key_id(&self) -> String {
+        String::from("plain")
+    }
+
+    fn encrypt(&self, _: &str, plaintext: &[u8]) -> Result<Vec<u8>, ResourceError> {
+        Ok(plaintext.to_vec())
+    }
+
+    fn decrypt(&self, _: &str, ciphertext: &[u8]) -> Result<Vec<u8>, ResourceError> {
+        Ok(ciphertext.to_vec())
+    }
+}
+
+#[allow(dead_code)]
+fn notes(id: AccountId) -> Result<Option<String>, ResourceError> {
+    Ok(AccountController.get_one(Box::new(accounts::id.eq(id)))?.inner.notes)
+}
+
+fn main() {
+    set_key_provider(PlainKeys);
+    assert_eq!(decrypt_value(&encrypt_value("123").unwrap()).unwrap(), "123");
+    assert!(AccountController::CREATE_TABLE.contains("ssn TEXT NOT NULL"));
+}
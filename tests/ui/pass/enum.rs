@@ -0,0 +1,55 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        use diesel::sql_types::*;
+        use crate::{AccountStatusType, AccountTierType};
+        accounts (id) {
+            id -> Int4,
+            status -> AccountStatusType,
+            tier -> AccountTierType,
+            tiers -> Array<AccountTierType>,
+        }
+    }
+}
+
+/// The status of an account.
+#[resource_enum]
+#[derive(PartialOrd, Debug)]
+pub enum AccountStatus {
+    Active,
+    Suspended,
+}
+
+#[resource_enum]
+#[type_name = "tier"]
+pub enum AccountTier {
+    Free,
+    Paid,
+}
+
+#[resource]
+struct Account {
+    status: AccountStatus,
+    #[sql_type = "tier"]
+    tier: AccountTier,
+    #[sql_type = "tier[]"]
+    tiers: Vec<AccountTier>,
+}
+
+fn main() {
+    assert!(AccountStatus::Active < AccountStatus::Suspended);
+    assert_eq!(<AccountStatus as ResourceEnum>::TYPE_NAME, "account_status");
+    assert_eq!(<AccountTier as ResourceEnum>::TYPE_NAME, "tier");
+    assert_eq!(AccountTier::CREATE_TYPE, "CREATE TYPE tier AS ENUM ('free', 'paid')");
+    assert!(AccountController::CREATE_TABLE.contains("status account_status NOT NULL"));
+}
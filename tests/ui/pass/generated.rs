@@ -0,0 +1,50 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            uuid -> Uuid,
+            username -> Text,
+            weight -> Int4,
+            search -> Text,
+        }
+    }
+}
+
+#[resource]
+struct Account {
+    #[generated(default = "gen_random_uuid()")]
+    uuid: Uuid,
+
+    username: String,
+
+    #[db_default(default = "5")]
+    weight: i32,
+
+    #[readonly(default = "''")]
+    search: String,
+}
+
+#[allow(dead_code)]
+fn read(account: AccountWithId) -> (Uuid, String, i32) {
+    (account.uuid, account.search, account.inner.weight.unwrap_or_default())
+}
+
+fn main() {
+    let account = Account { username: String::from("alice"), weight: None };
+    let _ = account;
+    assert!(AccountController::CREATE_TABLE.contains("uuid UUID NOT NULL DEFAULT gen_random_uuid()"));
+    assert!(AccountController::CREATE_TABLE.contains("weight INTEGER NOT NULL DEFAULT 5"));
+    assert!(AccountController::CREATE_TABLE.contains("search TEXT NOT NULL DEFAULT ''"));
+}
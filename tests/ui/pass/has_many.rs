@@ -0,0 +1,52 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+        }
+    }
+
+    table! {
+        sessions (id) {
+            id -> Int4,
+            account_id -> Int4,
+            token -> Text,
+            deleted_at -> Nullable<Timestamp>,
+        }
+    }
+}
+
+#[resource]
+#[has_many(Session)]
+struct Account {
+    username: String,
+}
+
+#[resource]
+#[soft_delete]
+struct Session {
+    #[fk(Account)]
+    account_id: AccountId,
+    token: String,
+}
+
+#[allow(dead_code)]
+fn live_sessions(username: &str) -> Result<Vec<(AccountWithId, Vec<SessionWithId>)>, ResourceError> {
+    AccountController.load_with_sessions(Box::new(accounts::username.eq(username.to_owned())))
+}
+
+fn main() {
+    let _: Expr<sessions::table> = SessionController::scope();
+    let _: Expr<accounts::table> = AccountController::scope();
+}
@@ -0,0 +1,75 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, pg::PgConnection, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            deleted_at -> Nullable<Timestamp>,
+        }
+    }
+
+    table! {
+        sessions (id) {
+            id -> Int4,
+            account_id -> Int4,
+            token -> Text,
+        }
+    }
+}
+
+#[resource]
+#[hooks]
+#[soft_delete]
+#[has_many(Session)]
+struct Account {
+    username: String,
+}
+
+#[resource]
+#[hooks]
+struct Session {
+    #[fk(Account)]
+    account_id: AccountId,
+    token: String,
+}
+
+impl ResourceHooks for AccountController {
+    fn before_create(&self, _: &PgConnection, model: &mut Account) -> Result<(), ResourceError> {
+        model.username = model.username.trim().to_string();
+        Ok(())
+    }
+
+    fn after_delete(
+        &self,
+        connection: &PgConnection,
+        deleted: &[AccountWithId],
+    ) -> Result<(), ResourceError> {
+        let ids = deleted.iter().map(|account| account.id).collect::<Vec<_>>();
+        delete(sessions::table.filter(sessions::account_id.eq_any(ids))).execute(connection)?;
+        Ok(())
+    }
+}
+
+impl ResourceHooks for SessionController {}
+
+#[allow(dead_code)]
+fn writes(account: &Account, sessions: &[Session]) -> Result<(), ResourceError> {
+    let (created, _) = AccountController.create_with_sessions(account, sessions)?;
+    AccountController.update(account, Box::new(accounts::id.eq(created.id)))?;
+    AccountController.delete(Box::new(accounts::id.eq(created.id)))?;
+    AccountController.restore(Box::new(accounts::id.eq(created.id)))?;
+    AccountController.purge(Box::new(accounts::id.eq(created.id)))?;
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,48 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            settings -> Jsonb,
+            extra -> Nullable<Jsonb>,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Settings {
+    theme: String,
+}
+
+#[resource]
+struct Account {
+    #[jsonb]
+    settings: Settings,
+    #[optional]
+    #[jsonb]
+    extra: serde_json::Value,
+}
+
+#[allow(dead_code)]
+fn dark_accounts() -> Result<Vec<AccountWithId>, ResourceError> {
+    let dark = AccountFilter::settings_contains(serde_json::json!({ "theme": "dark" }));
+    let tagged = AccountController.get_all(AccountFilter::extra_has_key("tag"))?;
+    assert!(tagged.iter().all(|account| account.inner.extra.is_some()));
+    AccountController.get_all(dark)
+}
+
+fn main() {
+    let account = Account { settings: Json(Settings { theme: String::from("dark") }), extra: None };
+    assert_eq!(account.settings.theme, "dark");
+    assert!(AccountController::CREATE_TABLE.contains("settings JSONB NOT NULL"));
+}
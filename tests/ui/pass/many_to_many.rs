@@ -0,0 +1,56 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+        }
+    }
+
+    table! {
+        roles (id) {
+            id -> Int4,
+            name -> Text,
+            deleted_at -> Nullable<Timestamp>,
+        }
+    }
+
+    table! {
+        account_roles (account_id, role_id) {
+            account_id -> Int4,
+            role_id -> Int4,
+        }
+    }
+}
+
+#[resource]
+#[many_to_many(Role, through = "account_roles")]
+struct Account {
+    username: String,
+}
+
+#[resource]
+#[soft_delete]
+struct Role {
+    name: String,
+}
+
+#[allow(dead_code)]
+fn grant(account: AccountId, roles: &[RoleId]) -> Result<Vec<RoleWithId>, ResourceError> {
+    AccountController.attach_roles(account, roles)?;
+    AccountController.detach_roles(account, &roles[..1])?;
+    AccountController.sync_roles(account, roles)?;
+    AccountController.load_roles(account)
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            display_name -> Text,
+            email -> Text,
+        }
+    }
+}
+
+#[resource]
+#[rename_all = "camelCase"]
+#[serde(deny_unknown_fields)]
+struct Account {
+    display_name: String,
+    #[serde(rename = "mail")]
+    email: String,
+}
+
+fn main() {
+    let account = Account { display_name: String::from("Alice"), email: String::from("a@b.c") };
+    let json = serde_json::to_string(&account).unwrap();
+    assert_eq!(json, r#"{"displayName":"Alice","mail":"a@b.c"}"#);
+    assert!(serde_json::from_str::<Account>(r#"{"displayName":"A","mail":"m","x":1}"#).is_err());
+}
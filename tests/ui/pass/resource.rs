@@ -0,0 +1,63 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            email -> Nullable<Text>,
+        }
+    }
+
+    table! {
+        sessions (id) {
+            id -> Int4,
+            account_id -> Int4,
+            revoked_by -> Nullable<Int4>,
+            token -> Text,
+        }
+    }
+}
+
+/// A user account.
+#[resource]
+pub struct Account {
+    username: String,
+
+    #[optional]
+    email: String,
+}
+
+#[resource]
+struct Session {
+    #[fk(Account)]
+    account_id: AccountId,
+    #[optional]
+    #[fk]
+    revoked_by: AccountId,
+    token: String,
+}
+
+#[allow(dead_code)]
+fn queries(account: &Account) -> Result<(), ResourceError> {
+    let account = AccountController.create(account)?;
+    let sessions = SessionController.create_for_account(account.id, &[Session { token: String::from("t") }], &AccountController.connection())?;
+    assert_eq!(SessionController.account(&sessions[0])?.id, account.id);
+    assert!(sessions[0].revoked_by.is_none());
+    assert_eq!(AccountController.get_all(Box::new(accounts::username.eq("alice")))?.len(), 1);
+    Ok(())
+}
+
+fn main() {
+    let _: &str = AccountController::CREATE_TABLE;
+    let _: &str = SessionController::DROP_TABLE;
+}
@@ -0,0 +1,42 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+            deleted_at -> Nullable<Timestamp>,
+        }
+    }
+}
+
+#[resource]
+#[timestamps]
+#[soft_delete]
+struct Account {
+    username: String,
+}
+
+#[allow(dead_code)]
+fn delete_and_restore(id: AccountId) -> Result<Option<chrono::NaiveDateTime>, ResourceError> {
+    AccountController.delete(Box::new(accounts::id.eq(id)))?;
+    let deleted = AccountController.with_deleted(Box::new(accounts::id.eq(id)))?;
+    AccountController.restore(Box::new(accounts::id.eq(id)))?;
+    AccountController.purge(Box::new(accounts::deleted_at.is_not_null()))?;
+    Ok(deleted.first().and_then(|account| account.deleted_at))
+}
+
+fn main() {
+    assert!(AccountController::CREATE_TABLE.contains("deleted_at TIMESTAMP"));
+}
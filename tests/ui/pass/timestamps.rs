@@ -0,0 +1,39 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use chrono::NaiveDateTime;
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            created_at -> Timestamp,
+            updated_at -> Timestamp,
+        }
+    }
+}
+
+#[resource]
+#[timestamps]
+struct Account {
+    username: String,
+}
+
+#[allow(dead_code)]
+fn rename(id: AccountId, username: &str) -> Result<(NaiveDateTime, NaiveDateTime), ResourceError> {
+    let renamed = Account { username: username.to_string() };
+    let updated = AccountController.update(&renamed, Box::new(accounts::id.eq(id)))?;
+    Ok((updated.created_at, updated.updated_at))
+}
+
+fn main() {
+    assert!(AccountController::CREATE_TABLE.contains("created_at TIMESTAMP NOT NULL DEFAULT now()"));
+}
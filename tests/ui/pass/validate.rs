@@ -0,0 +1,48 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            email -> Nullable<Text>,
+            age -> Int4,
+            tags -> Array<Text>,
+        }
+    }
+}
+
+#[resource]
+struct Account {
+    #[validate(length(min = 3, max = 32))]
+    username: String,
+
+    #[optional]
+    #[validate(email)]
+    email: String,
+
+    #[validate(range(min = 13))]
+    age: i32,
+
+    #[validate(length(max = 3))]
+    tags: Vec<String>,
+}
+
+fn main() {
+    let account = Account { username: String::from("alice"), email: None, age: 30, tags: Vec::new() };
+    assert_eq!(account.validate(), Ok(()));
+
+    let invalid = Account { email: Some(String::from("alice")), age: 12, ..account };
+    let errors = invalid.validate().unwrap_err();
+    assert_eq!(errors.field_errors()["email"], vec![ValidationError::Email]);
+    assert_eq!(errors.field_errors()["age"], vec![ValidationError::Range { min: Some(13.0), max: None }]);
+}
@@ -0,0 +1,38 @@
+#![allow(non_local_definitions)]
+
+#[macro_use]
+extern crate diesel;
+
+use diesel::{delete, insert_into, prelude::*, update};
+use postgres_resource::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::schema::*;
+
+mod schema {
+    table! {
+        accounts (id) {
+            id -> Int4,
+            username -> Text,
+            version -> Int4,
+        }
+    }
+}
+
+#[resource]
+struct Account {
+    username: String,
+    #[version]
+    version: i32,
+}
+
+#[allow(dead_code)]
+fn rename(id: AccountId, username: &str) -> Result<AccountWithId, ResourceError> {
+    let read = AccountController.get_one(Box::new(accounts::id.eq(id)))?;
+    let renamed = Account { username: username.to_string() };
+    AccountController.update_versioned(&renamed, read.version, Box::new(accounts::id.eq(id)))
+}
+
+fn main() {
+    assert!(AccountController::CREATE_TABLE.contains("version INTEGER NOT NULL DEFAULT 1"));
+}